@@ -0,0 +1,425 @@
+use rusqlite::{ffi, params, Connection, Result, Transaction};
+
+use crate::utils::{html_to_text, sanitize_html};
+
+/// Ordered schema migrations. Migration `i` upgrades the database from
+/// version `i` to version `i + 1`; the version is kept in `PRAGMA user_version`.
+/// Never edit a migration once released, append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    // 1: viewed articles, subscribed feeds and stored articles. Uses
+    // IF NOT EXISTS so databases created before versioning upgrade cleanly.
+    "CREATE TABLE IF NOT EXISTS viewed_articles (
+        article_url TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        viewed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    );
+    CREATE TABLE IF NOT EXISTS feeds (
+        url TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        added_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        position INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS articles (
+        feed_url TEXT NOT NULL,
+        entry_id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        link TEXT NOT NULL,
+        excerpt TEXT,
+        image_url TEXT,
+        published INTEGER,
+        fetched_at INTEGER NOT NULL,
+        PRIMARY KEY (feed_url, entry_id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_articles_published
+        ON articles (feed_url, published DESC);",
+    // 2: key read state on (feed_url, entry_id) instead of the article link.
+    // Rows are matched to stored articles by link; read state that cannot be
+    // matched to any stored article is dropped.
+    "CREATE TABLE viewed_articles_new (
+        feed_url TEXT NOT NULL,
+        entry_id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        viewed_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (feed_url, entry_id)
+    );
+    INSERT OR IGNORE INTO viewed_articles_new (feed_url, entry_id, title, viewed_at)
+        SELECT a.feed_url, a.entry_id, v.title, v.viewed_at
+        FROM viewed_articles v
+        JOIN articles a ON a.link = v.article_url;
+    DROP TABLE viewed_articles;
+    ALTER TABLE viewed_articles_new RENAME TO viewed_articles;",
+    // 3: update date, authors and categories of stored articles. Authors and
+    // categories are newline-separated lists.
+    "ALTER TABLE articles ADD COLUMN updated INTEGER;
+    ALTER TABLE articles ADD COLUMN authors TEXT NOT NULL DEFAULT '';
+    ALTER TABLE articles ADD COLUMN categories TEXT NOT NULL DEFAULT '';",
+    // 4: per-feed background refresh interval (NULL uses the global setting)
+    "ALTER TABLE feeds ADD COLUMN refresh_interval_minutes INTEGER;",
+    // 5: HTTP cache validators for conditional GET
+    "ALTER TABLE feeds ADD COLUMN etag TEXT;
+    ALTER TABLE feeds ADD COLUMN last_modified TEXT;",
+    // 6: feeds whose server answered 410 Gone
+    "ALTER TABLE feeds ADD COLUMN dead INTEGER NOT NULL DEFAULT 0;",
+    // 7: refresh health of each feed
+    "ALTER TABLE feeds ADD COLUMN last_success_at INTEGER;
+    ALTER TABLE feeds ADD COLUMN last_error TEXT;
+    ALTER TABLE feeds ADD COLUMN last_error_at INTEGER;
+    ALTER TABLE feeds ADD COLUMN consecutive_failures INTEGER NOT NULL DEFAULT 0;
+    ALTER TABLE feeds ADD COLUMN avg_latency_ms REAL;",
+    // 8: folders, nested at most one level deep
+    "CREATE TABLE folders (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        parent_id INTEGER REFERENCES folders (id),
+        collapsed INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE TRIGGER folders_nest_one_level
+        BEFORE INSERT ON folders
+        WHEN NEW.parent_id IS NOT NULL
+            AND NOT EXISTS (SELECT 1 FROM folders WHERE id = NEW.parent_id AND parent_id IS NULL)
+    BEGIN
+        SELECT RAISE(ABORT, 'parent folder does not exist or is itself a subfolder');
+    END;
+    ALTER TABLE feeds ADD COLUMN folder_id INTEGER REFERENCES folders (id);",
+    // 9: starred articles, snapshotted so they outlive their feed and the
+    // stored article
+    "CREATE TABLE starred_articles (
+        feed_url TEXT NOT NULL,
+        entry_id TEXT NOT NULL,
+        feed_title TEXT NOT NULL,
+        title TEXT NOT NULL,
+        link TEXT NOT NULL,
+        excerpt TEXT,
+        content TEXT,
+        image_url TEXT,
+        published INTEGER,
+        updated INTEGER,
+        authors TEXT NOT NULL DEFAULT '',
+        categories TEXT NOT NULL DEFAULT '',
+        starred_at INTEGER NOT NULL,
+        PRIMARY KEY (feed_url, entry_id)
+    );",
+    // 10: full article body, and a full-text index over stored articles kept
+    // in sync by triggers. The index is keyed by the articles' rowids, which
+    // VACUUM may renumber, so it has to be rebuilt after a VACUUM.
+    "ALTER TABLE articles ADD COLUMN content TEXT;
+    CREATE VIRTUAL TABLE articles_fts USING fts5(
+        title, excerpt, content, authors,
+        content = 'articles', content_rowid = 'rowid',
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    INSERT INTO articles_fts (articles_fts) VALUES ('rebuild');
+    CREATE TRIGGER articles_fts_insert AFTER INSERT ON articles BEGIN
+        INSERT INTO articles_fts (rowid, title, excerpt, content, authors)
+            VALUES (new.rowid, new.title, new.excerpt, new.content, new.authors);
+    END;
+    CREATE TRIGGER articles_fts_delete AFTER DELETE ON articles BEGIN
+        INSERT INTO articles_fts (articles_fts, rowid, title, excerpt, content, authors)
+            VALUES ('delete', old.rowid, old.title, old.excerpt, old.content, old.authors);
+    END;
+    CREATE TRIGGER articles_fts_update AFTER UPDATE ON articles BEGIN
+        INSERT INTO articles_fts (articles_fts, rowid, title, excerpt, content, authors)
+            VALUES ('delete', old.rowid, old.title, old.excerpt, old.content, old.authors);
+        INSERT INTO articles_fts (rowid, title, excerpt, content, authors)
+            VALUES (new.rowid, new.title, new.excerpt, new.content, new.authors);
+    END;",
+    // 11: saved searches shown as smart feeds
+    "CREATE TABLE saved_searches (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        query TEXT NOT NULL,
+        feed_url TEXT,
+        folder_id INTEGER REFERENCES folders (id),
+        unread_only INTEGER NOT NULL DEFAULT 0,
+        max_age_days INTEGER
+    );",
+    // 12: index the plain text of article bodies rather than their markup,
+    // so tag and attribute names neither match searches nor show in snippets
+    "ALTER TABLE articles ADD COLUMN content_text TEXT;
+    DROP TRIGGER articles_fts_insert;
+    DROP TRIGGER articles_fts_delete;
+    DROP TRIGGER articles_fts_update;
+    DROP TABLE articles_fts;
+    CREATE VIRTUAL TABLE articles_fts USING fts5(
+        title, excerpt, content_text, authors,
+        content = 'articles', content_rowid = 'rowid',
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    INSERT INTO articles_fts (articles_fts) VALUES ('rebuild');
+    CREATE TRIGGER articles_fts_insert AFTER INSERT ON articles BEGIN
+        INSERT INTO articles_fts (rowid, title, excerpt, content_text, authors)
+            VALUES (new.rowid, new.title, new.excerpt, new.content_text, new.authors);
+    END;
+    CREATE TRIGGER articles_fts_delete AFTER DELETE ON articles BEGIN
+        INSERT INTO articles_fts (articles_fts, rowid, title, excerpt, content_text, authors)
+            VALUES ('delete', old.rowid, old.title, old.excerpt, old.content_text, old.authors);
+    END;
+    CREATE TRIGGER articles_fts_update AFTER UPDATE ON articles BEGIN
+        INSERT INTO articles_fts (articles_fts, rowid, title, excerpt, content_text, authors)
+            VALUES ('delete', old.rowid, old.title, old.excerpt, old.content_text, old.authors);
+        INSERT INTO articles_fts (rowid, title, excerpt, content_text, authors)
+            VALUES (new.rowid, new.title, new.excerpt, new.content_text, new.authors);
+    END;",
+    // 13: reindex after `backfill_content_text` fills in the plain text of
+    // bodies stored before 12, which were indexed without it
+    "INSERT INTO articles_fts (articles_fts) VALUES ('rebuild');",
+    // 14: nothing but `resanitize_content`, which runs stored bodies through
+    // the allowlist sanitizer that replaced the regex-based one
+    "",
+    // 15: website of each feed, kept from OPML imports for exports
+    "ALTER TABLE feeds ADD COLUMN html_url TEXT;",
+];
+
+/// Steps SQL can't express, run in Rust right before the migration with the
+/// same index and in its transaction
+type DataMigration = fn(&Transaction) -> Result<()>;
+
+const DATA_MIGRATIONS: &[(usize, DataMigration)] = &[(12, backfill_content_text), (13, resanitize_content)];
+
+/// Bring the database schema up to date
+pub fn run_migrations(conn: &mut Connection) -> Result<()> {
+    apply(conn, MIGRATIONS, DATA_MIGRATIONS)
+}
+
+fn apply(conn: &mut Connection, migrations: &[&str], data_migrations: &[(usize, DataMigration)]) -> Result<()> {
+    let current = schema_version(conn)?;
+    let supported = migrations.len() as i64;
+    if current > supported {
+        return Err(rusqlite::Error::SqliteFailure(
+            ffi::Error::new(ffi::SQLITE_CANTOPEN),
+            Some(format!(
+                "database schema version {} is newer than the newest version supported \
+                 by this build ({}); please upgrade Czytaj",
+                current, supported
+            )),
+        ));
+    }
+
+    // Each migration runs in its own transaction together with the version
+    // bump, so a failure leaves the database at the last good version.
+    for (index, migration) in migrations.iter().enumerate().skip(current as usize) {
+        let tx = conn.transaction()?;
+        for (_, data_migration) in data_migrations.iter().filter(|(at, _)| *at == index) {
+            data_migration(&tx)?;
+        }
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index as i64 + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+/// Fill in the plain text of article bodies stored before it was kept
+fn backfill_content_text(tx: &Transaction) -> Result<()> {
+    let bodies = tx
+        .prepare("SELECT rowid, content FROM articles WHERE content IS NOT NULL AND content_text IS NULL")?
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>>>()?;
+    let mut update = tx.prepare("UPDATE articles SET content_text = ?1 WHERE rowid = ?2")?;
+    for (rowid, content) in bodies {
+        update.execute(params![html_to_text(&content), rowid])?;
+    }
+    Ok(())
+}
+
+/// Sanitize stored bodies and star snapshots again, resolving relative URLs
+/// against the article link, and refresh the plain text searched
+fn resanitize_content(tx: &Transaction) -> Result<()> {
+    let mut update = tx.prepare("UPDATE articles SET content = ?1, content_text = ?2 WHERE rowid = ?3")?;
+    for (rowid, content, link) in stored_bodies(tx, "articles")? {
+        let content = sanitize_html(&content, &link);
+        update.execute(params![content, html_to_text(&content), rowid])?;
+    }
+    let mut update = tx.prepare("UPDATE starred_articles SET content = ?1 WHERE rowid = ?2")?;
+    for (rowid, content, link) in stored_bodies(tx, "starred_articles")? {
+        update.execute(params![sanitize_html(&content, &link), rowid])?;
+    }
+    Ok(())
+}
+
+/// Rowid, body and link of every row of `table` that has a body
+fn stored_bodies(tx: &Transaction, table: &str) -> Result<Vec<(i64, String, String)>> {
+    tx.prepare(&format!("SELECT rowid, content, link FROM {} WHERE content IS NOT NULL", table))?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect()
+}
+
+/// Read the schema version stored in the database header
+fn schema_version(conn: &Connection) -> Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::ArticleDatabase;
+
+    const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+    /// Schema written by builds before versioning existed (user_version 0)
+    const V0_FIXTURE: &str = "
+        CREATE TABLE viewed_articles (
+            article_url TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            viewed_at DATETIME DEFAULT CURRENT_TIMESTAMP
+        );
+        INSERT INTO viewed_articles (article_url, title, viewed_at)
+            VALUES ('https://example.com/post', 'A post', '2024-01-01 10:00:00');";
+
+    fn table_exists(conn: &Connection, name: &str) -> bool {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [name],
+            |row| row.get::<_, i64>(0),
+        )
+        .unwrap()
+            > 0
+    }
+
+    #[test]
+    fn test_fresh_database_is_fully_migrated() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        for table in ["viewed_articles", "feeds", "articles"] {
+            assert!(table_exists(&conn, table), "missing table {}", table);
+        }
+    }
+
+    #[test]
+    fn test_v0_fixture_upgrades() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("czytaj-v0-fixture-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path).unwrap().execute_batch(V0_FIXTURE).unwrap();
+
+        let db = ArticleDatabase::open(&path).unwrap();
+        db.mark_as_viewed("https://example.com/feed", "entry-1", "A post").unwrap();
+        assert!(db.is_viewed("https://example.com/feed", "entry-1").unwrap());
+        drop(db);
+
+        let conn = Connection::open(&path).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert!(table_exists(&conn, "feeds"));
+        drop(conn);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_viewed_articles_are_rekeyed_by_entry_id() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        apply(&mut conn, &MIGRATIONS[..1], DATA_MIGRATIONS).unwrap();
+        conn.execute_batch(
+            "INSERT INTO articles (feed_url, entry_id, title, link, fetched_at)
+                VALUES ('https://example.com/feed', 'guid-1', 'A post', 'https://example.com/post', 0);
+             INSERT INTO viewed_articles (article_url, title)
+                VALUES ('https://example.com/post', 'A post'), ('https://gone.example/', 'Gone');",
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+        let rows: Vec<(String, String)> = conn
+            .prepare("SELECT feed_url, entry_id FROM viewed_articles")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(rows, vec![("https://example.com/feed".to_string(), "guid-1".to_string())]);
+    }
+
+    #[test]
+    fn test_existing_articles_are_indexed_for_search() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        apply(&mut conn, &MIGRATIONS[..9], DATA_MIGRATIONS).unwrap();
+        conn.execute(
+            "INSERT INTO articles (feed_url, entry_id, title, link, fetched_at)
+                VALUES ('https://example.com/feed', 'guid-1', 'Café opening', 'https://example.com/post', 0)",
+            [],
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+        let matches: i64 = conn
+            .query_row("SELECT COUNT(*) FROM articles_fts WHERE articles_fts MATCH 'cafe'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(matches, 1);
+    }
+
+    #[test]
+    fn test_bodies_stored_before_plain_text_are_searchable() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        apply(&mut conn, &MIGRATIONS[..11], DATA_MIGRATIONS).unwrap();
+        conn.execute(
+            "INSERT INTO articles (feed_url, entry_id, title, link, fetched_at, content)
+                VALUES ('https://example.com/feed', 'guid-1', 'A post', 'https://example.com/post', 0,
+                        '<p>Taming the <b>borrow</b> checker</p>')",
+            [],
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+        let matches: i64 = conn
+            .query_row("SELECT COUNT(*) FROM articles_fts WHERE articles_fts MATCH 'borrow'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(matches, 1);
+    }
+
+    #[test]
+    fn test_stored_bodies_are_sanitized_again() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        apply(&mut conn, &MIGRATIONS[..13], DATA_MIGRATIONS).unwrap();
+        conn.execute_batch(
+            "INSERT INTO articles (feed_url, entry_id, title, link, fetched_at, content)
+                VALUES ('https://example.com/feed', 'guid-1', 'A post', 'https://example.com/post', 0,
+                        '<p>Hi<img src=\"a.png\" onerror=alert(1)></p>');
+             INSERT INTO starred_articles (feed_url, entry_id, feed_title, title, link, starred_at, content)
+                VALUES ('https://example.com/feed', 'guid-2', 'Feed', 'Old', 'https://example.com/old', 0,
+                        '<a href=\"data:text/html,x\">x</a><svg onload=alert(1)></svg>');",
+        )
+        .unwrap();
+
+        run_migrations(&mut conn).unwrap();
+        let bodies: Vec<String> = conn
+            .prepare("SELECT content FROM articles UNION ALL SELECT content FROM starred_articles")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(
+            bodies,
+            ["<p>Hi<img src=\"https://example.com/a.png\"></p>", "<a rel=\"noopener noreferrer\">x</a>"]
+        );
+    }
+
+    #[test]
+    fn test_migrations_are_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run_migrations(&mut conn).unwrap();
+        run_migrations(&mut conn).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_failed_migration_rolls_back() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let migrations = [
+            "CREATE TABLE first (id INTEGER);",
+            "CREATE TABLE second (id INTEGER); NOT VALID SQL;",
+        ];
+        assert!(apply(&mut conn, &migrations, &[]).is_err());
+        assert_eq!(schema_version(&conn).unwrap(), 1);
+        assert!(table_exists(&conn, "first"));
+        assert!(!table_exists(&conn, "second"));
+    }
+
+    #[test]
+    fn test_newer_database_is_rejected() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", SCHEMA_VERSION + 1).unwrap();
+        let error = run_migrations(&mut conn).unwrap_err();
+        assert!(error.to_string().contains("newer than"), "unexpected error: {}", error);
+    }
+}
@@ -1,27 +1,58 @@
-use rusqlite::{Connection, Result};
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Result, Row};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
-/// Database manager for tracking viewed articles
+use crate::models::{
+    Article, CacheValidators, Feed, FeedHealth, Folder, RetentionSettings, SavedSearch, SearchResult, SnippetPart,
+    StarredArticle, StorageStats,
+};
+use crate::scheduler::MIN_REFRESH_INTERVAL_MINUTES;
+use crate::utils::{html_to_text, sanitize_html};
+
+mod migrations;
+
+/// Maximum number of articles in the merged timeline
+const TIMELINE_LIMIT: i64 = 1000;
+
+/// Maximum number of search results
+const SEARCH_LIMIT: i64 = 200;
+
+/// Snippet markers around matched terms; control characters can't occur in
+/// the stored text
+const HIGHLIGHT_START: char = '\u{2}';
+const HIGHLIGHT_END: char = '\u{3}';
+
+/// Conditions over `articles a`, `feeds f` and `viewed_articles v` selecting
+/// the articles of a saved search, with parameters ?1 query, ?2 feed URL,
+/// ?3 folder, ?4 unread only and ?5 oldest timestamp
+const SAVED_SEARCH_CONDITIONS: &str =
+    "(?1 IS NULL OR a.rowid IN (SELECT rowid FROM articles_fts WHERE articles_fts MATCH ?1))
+     AND (?2 IS NULL OR a.feed_url = ?2)
+     AND (?3 IS NULL OR f.folder_id = ?3 OR f.folder_id IN (SELECT id FROM folders WHERE parent_id = ?3))
+     AND (NOT ?4 OR v.entry_id IS NULL)
+     AND (?5 IS NULL OR COALESCE(a.published, a.updated, a.fetched_at) >= ?5)";
+
+/// Columns read by `health_from_row`
+const HEALTH_COLUMNS: &str =
+    "last_success_at, last_error, last_error_at, consecutive_failures, avg_latency_ms";
+
+/// Database manager for subscribed feeds, stored articles and their read state
 pub struct ArticleDatabase {
     conn: Arc<Mutex<Connection>>,
 }
 
 impl ArticleDatabase {
-    /// Create a new database connection and initialize tables
+    /// Open the database in the user's data directory and bring its schema up to date
     pub fn new() -> Result<Self> {
-        let db_path = Self::get_db_path();
-        let conn = Connection::open(db_path)?;
-        
-        // Create the viewed_articles table if it doesn't exist
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS viewed_articles (
-                article_url TEXT PRIMARY KEY,
-                title TEXT NOT NULL,
-                viewed_at DATETIME DEFAULT CURRENT_TIMESTAMP
-            )",
-            [],
-        )?;
+        Self::open(Self::get_db_path())
+    }
+
+    /// Open the database at `path` and bring its schema up to date
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut conn = Connection::open(path)?;
+        migrations::run_migrations(&mut conn)?;
         
         Ok(Self {
             conn: Arc::new(Mutex::new(conn)),
@@ -40,37 +71,819 @@ impl ArticleDatabase {
     }
     
     /// Mark an article as viewed
-    pub fn mark_as_viewed(&self, article_url: &str, title: &str) -> Result<()> {
+    pub fn mark_as_viewed(&self, feed_url: &str, entry_id: &str, title: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT OR REPLACE INTO viewed_articles (article_url, title, viewed_at) 
-             VALUES (?1, ?2, CURRENT_TIMESTAMP)",
-            [article_url, title],
+            "INSERT OR REPLACE INTO viewed_articles (feed_url, entry_id, title, viewed_at) 
+             VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP)",
+            [feed_url, entry_id, title],
         )?;
         Ok(())
     }
     
+    /// Mark an article as unread again
+    pub fn mark_as_unread(&self, feed_url: &str, entry_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM viewed_articles WHERE feed_url = ?1 AND entry_id = ?2",
+            [feed_url, entry_id],
+        )?;
+        Ok(())
+    }
+
+    /// Mark the stored or starred articles with the given `(feed_url, entry_id)`
+    /// keys as read or unread, returning how many changed
+    pub fn set_articles_read(&self, keys: &[(String, String)], read: bool) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut changed = 0;
+        {
+            // A starred snapshot may outlive its article, so it can be read too
+            let mut stmt = tx.prepare(if read {
+                "INSERT OR IGNORE INTO viewed_articles (feed_url, entry_id, title, viewed_at)
+                 SELECT feed_url, entry_id, title, CURRENT_TIMESTAMP FROM (
+                     SELECT feed_url, entry_id, title, 0 AS source FROM articles WHERE feed_url = ?1 AND entry_id = ?2
+                     UNION ALL
+                     SELECT feed_url, entry_id, title, 1 FROM starred_articles WHERE feed_url = ?1 AND entry_id = ?2
+                 )
+                 ORDER BY source
+                 LIMIT 1"
+            } else {
+                "DELETE FROM viewed_articles WHERE feed_url = ?1 AND entry_id = ?2"
+            })?;
+            for (feed_url, entry_id) in keys {
+                changed += stmt.execute([feed_url, entry_id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(changed)
+    }
+
     /// Check if an article has been viewed
-    pub fn is_viewed(&self, article_url: &str) -> Result<bool> {
+    pub fn is_viewed(&self, feed_url: &str, entry_id: &str) -> Result<bool> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT COUNT(*) FROM viewed_articles WHERE article_url = ?1"
+            "SELECT COUNT(*) FROM viewed_articles WHERE feed_url = ?1 AND entry_id = ?2"
         )?;
-        let count: i64 = stmt.query_row([article_url], |row| row.get(0))?;
+        let count: i64 = stmt.query_row([feed_url, entry_id], |row| row.get(0))?;
         Ok(count > 0)
     }
     
-    /// Get all viewed article URLs
-    pub fn get_viewed_articles(&self) -> Result<Vec<String>> {
+    /// Get the `(feed_url, entry_id)` keys of all viewed articles
+    pub fn get_viewed_articles(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT feed_url, entry_id FROM viewed_articles ORDER BY viewed_at DESC"
+        )?;
+        let articles = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<(String, String)>>>()?;
+        Ok(articles)
+    }
+
+    /// Subscribe to a feed, appending it to the end of the list.
+    /// If the feed is already subscribed only its title is updated.
+    pub fn add_feed(&self, url: &str, title: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO feeds (url, title, added_at, position)
+             VALUES (?1, ?2, CURRENT_TIMESTAMP,
+                     (SELECT COALESCE(MAX(position) + 1, 0) FROM feeds))
+             ON CONFLICT(url) DO UPDATE SET title = excluded.title",
+            [url, title],
+        )?;
+        Ok(())
+    }
+
+    /// Subscribe to several `(url, title, html_url)` feeds at once, e.g. from
+    /// an OPML import. Feeds already subscribed are left as they are. Returns
+    /// how many were added.
+    pub fn add_feeds<'a>(
+        &self,
+        feeds: impl IntoIterator<Item = (&'a str, &'a str, Option<&'a str>)>,
+    ) -> Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let mut added = 0;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO feeds (url, title, html_url, added_at, position)
+                 VALUES (?1, ?2, ?3, CURRENT_TIMESTAMP,
+                         (SELECT COALESCE(MAX(position) + 1, 0) FROM feeds))
+                 ON CONFLICT(url) DO NOTHING",
+            )?;
+            for (url, title, html_url) in feeds {
+                added += stmt.execute(params![url, title, html_url])?;
+            }
+        }
+        tx.commit()?;
+        Ok(added)
+    }
+
+    /// Unsubscribe from a feed
+    pub fn remove_feed(&self, url: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM feeds WHERE url = ?1", [url])?;
+        Ok(())
+    }
+
+    /// Move a subscription to a new URL together with its stored articles,
+    /// read state, starred articles and the saved searches limited to it.
+    /// If the new URL is already subscribed the two are merged.
+    pub fn rename_feed(&self, old_url: &str, new_url: &str) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute("UPDATE saved_searches SET feed_url = ?1 WHERE feed_url = ?2", [new_url, old_url])?;
+        for table in ["articles", "viewed_articles", "starred_articles"] {
+            tx.execute(
+                &format!("UPDATE OR IGNORE {} SET feed_url = ?1 WHERE feed_url = ?2", table),
+                [new_url, old_url],
+            )?;
+            tx.execute(&format!("DELETE FROM {} WHERE feed_url = ?1", table), [old_url])?;
+        }
+        tx.execute("UPDATE OR IGNORE feeds SET url = ?1 WHERE url = ?2", [new_url, old_url])?;
+        tx.execute("DELETE FROM feeds WHERE url = ?1", [old_url])?;
+        tx.commit()
+    }
+
+    /// Mark a feed as gone, or as alive again after a successful fetch
+    pub fn set_feed_dead(&self, url: &str, dead: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE feeds SET dead = ?1 WHERE url = ?2", params![dead, url])?;
+        Ok(())
+    }
+
+    /// Get all subscribed feeds in display order
+    pub fn list_feeds(&self) -> Result<Vec<Feed>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT url, title, refresh_interval_minutes, dead, folder_id, html_url, {}
+             FROM feeds ORDER BY position, added_at",
+            HEALTH_COLUMNS
+        ))?;
+        let feeds = stmt
+            .query_map([], |row| {
+                let mut feed = Feed::new(row.get(0)?, row.get(1)?);
+                feed.refresh_interval_minutes = row.get(2)?;
+                feed.dead = row.get(3)?;
+                feed.folder_id = row.get(4)?;
+                feed.html_url = row.get(5)?;
+                feed.health = health_from_row(row, 6)?;
+                Ok(feed)
+            })?
+            .collect::<Result<Vec<Feed>>>()?;
+        Ok(feeds)
+    }
+
+    /// File a feed in a folder, or back at the top level with `None`
+    pub fn set_feed_folder(&self, url: &str, folder_id: Option<i64>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE feeds SET folder_id = ?1 WHERE url = ?2", params![folder_id, url])?;
+        Ok(())
+    }
+
+    /// Get all folders, sorted by name
+    pub fn list_folders(&self) -> Result<Vec<Folder>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, parent_id, collapsed FROM folders ORDER BY name COLLATE NOCASE, id"
+        )?;
+        let folders = stmt
+            .query_map([], |row| {
+                Ok(Folder {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    parent_id: row.get(2)?,
+                    collapsed: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<Folder>>>()?;
+        Ok(folders)
+    }
+
+    /// Create a folder, inside `parent_id` if given, returning its id.
+    /// Fails if the parent is itself a subfolder.
+    pub fn create_folder(&self, name: &str, parent_id: Option<i64>) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO folders (name, parent_id) VALUES (?1, ?2)",
+            params![name, parent_id],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn rename_folder(&self, id: i64, name: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE folders SET name = ?1 WHERE id = ?2", params![name, id])?;
+        Ok(())
+    }
+
+    /// Remember whether a folder's sidebar section is folded away
+    pub fn set_folder_collapsed(&self, id: i64, collapsed: bool) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE folders SET collapsed = ?1 WHERE id = ?2", params![collapsed, id])?;
+        Ok(())
+    }
+
+    /// Delete a folder without unsubscribing from anything: its feeds move
+    /// up to the parent folder (or the top level) and its subfolders become
+    /// top-level folders
+    pub fn delete_folder(&self, id: i64) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        tx.execute(
+            "UPDATE feeds SET folder_id = (SELECT parent_id FROM folders WHERE id = ?1)
+             WHERE folder_id = ?1",
+            [id],
+        )?;
+        tx.execute(
+            "UPDATE saved_searches SET folder_id = (SELECT parent_id FROM folders WHERE id = ?1)
+             WHERE folder_id = ?1",
+            [id],
+        )?;
+        tx.execute("UPDATE folders SET parent_id = NULL WHERE parent_id = ?1", [id])?;
+        tx.execute("DELETE FROM folders WHERE id = ?1", [id])?;
+        tx.commit()
+    }
+
+    /// Number of unread stored articles of each subscribed feed, leaving out
+    /// feeds that have none
+    pub fn unread_counts(&self) -> Result<HashMap<String, usize>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT a.feed_url, COUNT(*)
+             FROM articles a
+             JOIN feeds f ON f.url = a.feed_url
+             LEFT JOIN viewed_articles v ON v.feed_url = a.feed_url AND v.entry_id = a.entry_id
+             WHERE v.entry_id IS NULL
+             GROUP BY a.feed_url"
+        )?;
+        let counts = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))?
+            .collect::<Result<HashMap<String, usize>>>()?;
+        Ok(counts)
+    }
+
+    /// Number of unread articles across all subscribed feeds
+    pub fn total_unread(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*)
+             FROM articles a
+             JOIN feeds f ON f.url = a.feed_url
+             LEFT JOIN viewed_articles v ON v.feed_url = a.feed_url AND v.entry_id = a.entry_id
+             WHERE v.entry_id IS NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Mark every stored article of a feed as read, returning how many were unread
+    pub fn mark_feed_read(&self, feed_url: &str) -> Result<usize> {
+        self.mark_read_where("a.feed_url = ?1", params![feed_url])
+    }
+
+    /// Mark every article of every subscribed feed as read, returning how
+    /// many were unread
+    pub fn mark_all_read(&self) -> Result<usize> {
+        self.mark_read_where("1", params![])
+    }
+
+    /// Mark articles published more than `days` days ago as read, returning
+    /// how many were unread. Undated articles go by when they were fetched.
+    pub fn mark_read_older_than(&self, days: u32) -> Result<usize> {
+        self.mark_read_where("COALESCE(a.published, a.updated, a.fetched_at) < ?1", params![days_ago(days)])
+    }
+
+    /// Mark the unread articles of subscribed feeds matching `condition`
+    /// (over `articles a`) as read. Articles already read keep the time they
+    /// were first read.
+    fn mark_read_where(&self, condition: &str, params: &[&dyn rusqlite::ToSql]) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            &format!(
+                "INSERT OR IGNORE INTO viewed_articles (feed_url, entry_id, title, viewed_at)
+                 SELECT a.feed_url, a.entry_id, a.title, CURRENT_TIMESTAMP
+                 FROM articles a
+                 JOIN feeds f ON f.url = a.feed_url
+                 WHERE {}",
+                condition
+            ),
+            params,
+        )
+    }
+
+    /// Override the background refresh interval of a feed; `None` restores
+    /// the global setting. Intervals are at least a minute long.
+    pub fn set_feed_refresh_interval(&self, url: &str, minutes: Option<u64>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE feeds SET refresh_interval_minutes = ?1 WHERE url = ?2",
+            params![minutes.map(|minutes| minutes.max(MIN_REFRESH_INTERVAL_MINUTES)), url],
+        )?;
+        Ok(())
+    }
+
+    /// Apply `update` to the health of a subscribed feed and store the result.
+    /// Returns the new health, or `None` if the feed isn't subscribed.
+    pub fn update_feed_health(
+        &self,
+        url: &str,
+        update: impl FnOnce(&mut FeedHealth),
+    ) -> Result<Option<FeedHealth>> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let health = tx
+            .query_row(
+                &format!("SELECT {} FROM feeds WHERE url = ?1", HEALTH_COLUMNS),
+                [url],
+                |row| health_from_row(row, 0),
+            )
+            .optional()?;
+        let Some(mut health) = health else {
+            return Ok(None);
+        };
+
+        update(&mut health);
+        tx.execute(
+            "UPDATE feeds SET last_success_at = ?1, last_error = ?2, last_error_at = ?3,
+                consecutive_failures = ?4, avg_latency_ms = ?5
+             WHERE url = ?6",
+            params![
+                health.last_success_at.map(|d| d.timestamp()),
+                health.last_error,
+                health.last_error_at.map(|d| d.timestamp()),
+                health.consecutive_failures,
+                health.avg_latency_ms,
+                url,
+            ],
+        )?;
+        tx.commit()?;
+        Ok(Some(health))
+    }
+
+    /// HTTP cache validators from the last successful fetch of a feed
+    pub fn get_cache_validators(&self, url: &str) -> Result<CacheValidators> {
+        let conn = self.conn.lock().unwrap();
+        let validators = conn
+            .query_row(
+                "SELECT etag, last_modified FROM feeds WHERE url = ?1",
+                [url],
+                |row| {
+                    Ok(CacheValidators {
+                        etag: row.get(0)?,
+                        last_modified: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(validators.unwrap_or_default())
+    }
+
+    /// Remember the HTTP cache validators of a subscribed feed
+    pub fn set_cache_validators(&self, url: &str, validators: &CacheValidators) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "UPDATE feeds SET etag = ?1, last_modified = ?2 WHERE url = ?3",
+            params![validators.etag, validators.last_modified, url],
+        )?;
+        Ok(())
+    }
+
+    /// Store a new display order; `urls` lists the feeds from top to bottom
+    pub fn reorder_feeds(&self, urls: &[String]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("UPDATE feeds SET position = ?1 WHERE url = ?2")?;
+            for (position, url) in urls.iter().enumerate() {
+                stmt.execute(params![position as i64, url])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Insert freshly fetched articles for a feed, updating entries that
+    /// were already stored. Articles that dropped out of the feed are kept.
+    pub fn upsert_articles(&self, feed_url: &str, articles: &[Article]) -> Result<()> {
+        let fetched_at = Utc::now().timestamp();
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO articles
+                    (feed_url, entry_id, title, link, excerpt, image_url, published,
+                     updated, authors, categories, fetched_at, content, content_text)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                 ON CONFLICT(feed_url, entry_id) DO UPDATE SET
+                    title = excluded.title,
+                    link = excluded.link,
+                    excerpt = excluded.excerpt,
+                    content = excluded.content,
+                    content_text = excluded.content_text,
+                    image_url = excluded.image_url,
+                    published = excluded.published,
+                    updated = excluded.updated,
+                    authors = excluded.authors,
+                    categories = excluded.categories,
+                    fetched_at = excluded.fetched_at",
+            )?;
+            for article in articles {
+                stmt.execute(params![
+                    feed_url,
+                    article.id,
+                    article.title,
+                    article.link,
+                    article.excerpt,
+                    article.image_url,
+                    article.published.map(|d| d.timestamp()),
+                    article.updated.map(|d| d.timestamp()),
+                    article.authors.join("\n"),
+                    article.categories.join("\n"),
+                    fetched_at,
+                    article.content,
+                    article.content.as_deref().map(html_to_text),
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Star an article, saving a snapshot of it. Starring it again keeps the
+    /// original snapshot. The snapshot takes the stored body of articles
+    /// loaded without one; a body passed in is sanitized first.
+    pub fn star_article(&self, article: &Article, feed_title: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO starred_articles
+                (feed_url, entry_id, feed_title, title, link, excerpt, image_url, published,
+                 updated, authors, categories, starred_at, content)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12,
+                     COALESCE(?13, (SELECT content FROM articles WHERE feed_url = ?1 AND entry_id = ?2)))
+             ON CONFLICT(feed_url, entry_id) DO NOTHING",
+            params![
+                article.feed_url,
+                article.id,
+                feed_title,
+                article.title,
+                article.link,
+                article.excerpt,
+                article.image_url,
+                article.published.map(|d| d.timestamp()),
+                article.updated.map(|d| d.timestamp()),
+                article.authors.join("\n"),
+                article.categories.join("\n"),
+                Utc::now().timestamp(),
+                article.content.as_deref().map(|content| sanitize_html(content, &article.link)),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Full body of an article, from the stored article or else from its
+    /// starred snapshot. The body is sanitized again on every read: what was
+    /// stored may have been cleaned by an older, weaker sanitizer.
+    pub fn get_article_content(&self, feed_url: &str, entry_id: &str) -> Result<Option<String>> {
+        let conn = self.conn.lock().unwrap();
+        let stored: Option<(String, String)> = conn
+            .query_row(
+                "SELECT content, link FROM (
+                     SELECT content, link, 0 AS source FROM articles WHERE feed_url = ?1 AND entry_id = ?2
+                     UNION ALL
+                     SELECT content, link, 1 FROM starred_articles WHERE feed_url = ?1 AND entry_id = ?2
+                 )
+                 WHERE content IS NOT NULL
+                 ORDER BY source
+                 LIMIT 1",
+                [feed_url, entry_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(stored.map(|(content, link)| sanitize_html(&content, &link)))
+    }
+
+    pub fn unstar_article(&self, feed_url: &str, entry_id: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "DELETE FROM starred_articles WHERE feed_url = ?1 AND entry_id = ?2",
+            [feed_url, entry_id],
+        )?;
+        Ok(())
+    }
+
+    pub fn is_starred(&self, feed_url: &str, entry_id: &str) -> Result<bool> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT EXISTS (SELECT 1 FROM starred_articles WHERE feed_url = ?1 AND entry_id = ?2)",
+            [feed_url, entry_id],
+            |row| row.get(0),
+        )
+    }
+
+    /// Get every starred article, most recently starred first
+    pub fn list_starred(&self) -> Result<Vec<StarredArticle>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT feed_url, entry_id, title, link, excerpt, image_url, published,
+                    updated, authors, categories, feed_title, starred_at
+             FROM starred_articles
+             ORDER BY starred_at DESC"
+        )?;
+        let starred = stmt
+            .query_map([], |row| {
+                Ok(StarredArticle {
+                    article: article_from_row(row)?,
+                    feed_title: row.get(10)?,
+                    starred_at: timestamp_from_row(row, 11)?.unwrap_or_default(),
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(starred)
+    }
+
+    /// Search the stored articles of subscribed feeds, best matches first.
+    /// Every word of `query` must match, as a prefix, unless words are joined
+    /// by `OR`; titles weigh most. Results can be limited to one feed, to the
+    /// feeds in a folder and its subfolders, to unread articles and to the
+    /// last `max_age_days` days.
+    pub fn search_articles(
+        &self,
+        query: &str,
+        feed_url: Option<&str>,
+        folder_id: Option<i64>,
+        unread_only: bool,
+        max_age_days: Option<u32>,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT a.feed_url, a.entry_id, a.title, a.link, a.excerpt, a.image_url, a.published,
+                    a.updated, a.authors, a.categories,
+                    snippet(articles_fts, -1, char(2), char(3), '…', 24)
+             FROM articles_fts
+             JOIN articles a ON a.rowid = articles_fts.rowid
+             JOIN feeds f ON f.url = a.feed_url
+             LEFT JOIN viewed_articles v ON v.feed_url = a.feed_url AND v.entry_id = a.entry_id
+             WHERE articles_fts MATCH ?1
+               AND (?2 IS NULL OR a.feed_url = ?2)
+               AND (?3 IS NULL OR f.folder_id = ?3 OR f.folder_id IN (SELECT id FROM folders WHERE parent_id = ?3))
+               AND (NOT ?4 OR v.entry_id IS NULL)
+               AND (?5 IS NULL OR COALESCE(a.published, a.updated, a.fetched_at) >= ?5)
+             ORDER BY bm25(articles_fts, 10.0, 2.0, 1.0, 5.0)
+             LIMIT ?6"
+        )?;
+        let since = max_age_days.map(days_ago);
+        let results = stmt
+            .query_map(params![query, feed_url, folder_id, unread_only, since, SEARCH_LIMIT], |row| {
+                Ok(SearchResult {
+                    article: article_from_row(row)?,
+                    snippet: snippet_parts(&row.get::<_, String>(10)?),
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(results)
+    }
+
+    pub fn list_saved_searches(&self) -> Result<Vec<SavedSearch>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
-            "SELECT article_url FROM viewed_articles ORDER BY viewed_at DESC"
+            "SELECT id, name, query, feed_url, folder_id, unread_only, max_age_days
+             FROM saved_searches
+             ORDER BY name COLLATE NOCASE"
         )?;
+        let searches = stmt
+            .query_map([], |row| {
+                Ok(SavedSearch {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    query: row.get(2)?,
+                    feed_url: row.get(3)?,
+                    folder_id: row.get(4)?,
+                    unread_only: row.get(5)?,
+                    max_age_days: row.get(6)?,
+                })
+            })?
+            .collect::<Result<Vec<_>>>()?;
+        Ok(searches)
+    }
+
+    /// Save a search, returning its ID. The `id` of `search` is ignored.
+    pub fn create_saved_search(&self, search: &SavedSearch) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO saved_searches (name, query, feed_url, folder_id, unread_only, max_age_days)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                search.name,
+                search.query,
+                search.feed_url,
+                search.folder_id,
+                search.unread_only,
+                search.max_age_days,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    pub fn delete_saved_search(&self, id: i64) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM saved_searches WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    /// Get the articles currently matching a saved search, newest first
+    pub fn get_saved_search_articles(&self, search: &SavedSearch) -> Result<Vec<Article>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT a.feed_url, a.entry_id, a.title, a.link, a.excerpt, a.image_url, a.published,
+                    a.updated, a.authors, a.categories
+             FROM articles a
+             JOIN feeds f ON f.url = a.feed_url
+             LEFT JOIN viewed_articles v ON v.feed_url = a.feed_url AND v.entry_id = a.entry_id
+             WHERE {}
+             ORDER BY COALESCE(a.published, a.updated, a.fetched_at) DESC
+             LIMIT ?6",
+            SAVED_SEARCH_CONDITIONS
+        ))?;
+        let (query, since) = saved_search_bounds(search);
         let articles = stmt
-            .query_map([], |row| row.get(0))?
-            .collect::<Result<Vec<String>>>()?;
+            .query_map(
+                params![query, search.feed_url, search.folder_id, search.unread_only, since, TIMELINE_LIMIT],
+                article_from_row,
+            )?
+            .collect::<Result<Vec<_>>>()?;
         Ok(articles)
     }
+
+    /// Number of unread articles matching each saved search, keyed by ID
+    pub fn saved_search_unread_counts(&self) -> Result<HashMap<i64, usize>> {
+        let searches = self.list_saved_searches()?;
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT COUNT(*)
+             FROM articles a
+             JOIN feeds f ON f.url = a.feed_url
+             LEFT JOIN viewed_articles v ON v.feed_url = a.feed_url AND v.entry_id = a.entry_id
+             WHERE {} AND v.entry_id IS NULL",
+            SAVED_SEARCH_CONDITIONS
+        ))?;
+        let mut counts = HashMap::new();
+        for search in &searches {
+            let (query, since) = saved_search_bounds(search);
+            let count: i64 = stmt.query_row(
+                params![query, search.feed_url, search.folder_id, search.unread_only, since],
+                |row| row.get(0),
+            )?;
+            counts.insert(search.id, count as usize);
+        }
+        Ok(counts)
+    }
+
+    /// Get the merged timeline of all subscribed feeds, newest first
+    pub fn get_timeline(&self) -> Result<Vec<Article>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT a.feed_url, a.entry_id, a.title, a.link, a.excerpt, a.image_url, a.published,
+                    a.updated, a.authors, a.categories
+             FROM articles a
+             JOIN feeds f ON f.url = a.feed_url
+             ORDER BY COALESCE(a.published, a.updated, a.fetched_at) DESC
+             LIMIT ?1"
+        )?;
+        let articles = stmt
+            .query_map([TIMELINE_LIMIT], article_from_row)?
+            .collect::<Result<Vec<Article>>>()?;
+        Ok(articles)
+    }
+
+    /// Get the merged timeline of the feeds in a folder and its subfolders,
+    /// newest first
+    pub fn get_folder_timeline(&self, folder_id: i64) -> Result<Vec<Article>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT a.feed_url, a.entry_id, a.title, a.link, a.excerpt, a.image_url, a.published,
+                    a.updated, a.authors, a.categories
+             FROM articles a
+             JOIN feeds f ON f.url = a.feed_url
+             WHERE f.folder_id IN (SELECT id FROM folders WHERE id = ?1 OR parent_id = ?1)
+             ORDER BY COALESCE(a.published, a.updated, a.fetched_at) DESC
+             LIMIT ?2"
+        )?;
+        let articles = stmt
+            .query_map([folder_id, TIMELINE_LIMIT], article_from_row)?
+            .collect::<Result<Vec<Article>>>()?;
+        Ok(articles)
+    }
+
+    /// Get every stored article of a feed, newest first
+    pub fn get_articles(&self, feed_url: &str) -> Result<Vec<Article>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT feed_url, entry_id, title, link, excerpt, image_url, published,
+                    updated, authors, categories
+             FROM articles
+             WHERE feed_url = ?1
+             ORDER BY COALESCE(published, updated, fetched_at) DESC"
+        )?;
+        let articles = stmt
+            .query_map([feed_url], article_from_row)?
+            .collect::<Result<Vec<Article>>>()?;
+        Ok(articles)
+    }
+
+    /// Purge articles past the retention limits, with the read state of
+    /// purged articles, returning how many were purged. Starred articles are
+    /// kept, and so are articles the latest fetch of their feed still listed,
+    /// since the next fetch would store them again. Articles of unsubscribed
+    /// feeds are purged regardless of the limits.
+    pub fn apply_retention(&self, retention: &RetentionSettings) -> Result<usize> {
+        let oldest = (retention.max_age_days > 0).then(|| days_ago(retention.max_age_days));
+        let max_per_feed = (retention.max_articles_per_feed > 0).then_some(retention.max_articles_per_feed);
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let purged = tx.execute(
+            "WITH ranked AS (
+                 SELECT rowid AS id, feed_url, entry_id, fetched_at,
+                        COALESCE(published, updated, fetched_at) AS date,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY feed_url ORDER BY COALESCE(published, updated, fetched_at) DESC
+                        ) AS position,
+                        MAX(fetched_at) OVER (PARTITION BY feed_url) AS last_fetched_at
+                 FROM articles
+             )
+             DELETE FROM articles WHERE rowid IN (
+                 SELECT r.id FROM ranked r
+                 WHERE NOT EXISTS (
+                         SELECT 1 FROM starred_articles s
+                         WHERE s.feed_url = r.feed_url AND s.entry_id = r.entry_id
+                       )
+                   AND (
+                         r.feed_url NOT IN (SELECT url FROM feeds)
+                         OR (r.fetched_at < r.last_fetched_at
+                             AND (r.date < ?1 OR r.position > ?2))
+                       )
+             )",
+            params![oldest, max_per_feed],
+        )?;
+        // Read state outlives its article only while the article is starred
+        tx.execute(
+            "DELETE FROM viewed_articles
+             WHERE NOT EXISTS (
+                     SELECT 1 FROM articles a
+                     WHERE a.feed_url = viewed_articles.feed_url AND a.entry_id = viewed_articles.entry_id
+                   )
+               AND NOT EXISTS (
+                     SELECT 1 FROM starred_articles s
+                     WHERE s.feed_url = viewed_articles.feed_url AND s.entry_id = viewed_articles.entry_id
+                   )",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(purged)
+    }
+
+    /// Purge articles past the retention limits, then compact the database
+    /// if that deleted any or `force` is set. Returns how many were purged.
+    pub fn clean_up(&self, retention: &RetentionSettings, force: bool) -> Result<usize> {
+        let purged = self.apply_retention(retention)?;
+        if purged > 0 || force {
+            self.compact(force)?;
+        }
+        Ok(purged)
+    }
+
+    /// Refresh the query planner's statistics and give free space back to
+    /// the file system. Rewriting the file is slow, so unless `force` is set
+    /// that only happens once a quarter of it is free.
+    pub fn compact(&self, force: bool) -> Result<()> {
+        let stats = self.storage_stats()?;
+        let conn = self.conn.lock().unwrap();
+        if force || stats.free_bytes * 4 > stats.database_bytes {
+            conn.execute_batch("VACUUM;")?;
+            // VACUUM may renumber the articles the search index refers to
+            conn.execute("INSERT INTO articles_fts (articles_fts) VALUES ('rebuild')", [])?;
+        }
+        conn.execute_batch("ANALYZE;")
+    }
+
+    pub fn storage_stats(&self) -> Result<StorageStats> {
+        let conn = self.conn.lock().unwrap();
+        let pragma = |name: &str| conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get::<_, i64>(0));
+        let count = |table: &str| {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get::<_, i64>(0))
+        };
+        let page_size = pragma("page_size")? as u64;
+        Ok(StorageStats {
+            database_bytes: pragma("page_count")? as u64 * page_size,
+            free_bytes: pragma("freelist_count")? as u64 * page_size,
+            articles: count("articles")? as usize,
+            starred: count("starred_articles")? as usize,
+            read_markers: count("viewed_articles")? as usize,
+        })
+    }
 }
 
 impl Clone for ArticleDatabase {
@@ -80,3 +893,400 @@ impl Clone for ArticleDatabase {
         }
     }
 }
+
+/// Build an article from a row of `feed_url, entry_id, title, link, excerpt,
+/// image_url, published, updated, authors, categories`
+fn article_from_row(row: &Row) -> Result<Article> {
+    let timestamp = |index| timestamp_from_row(row, index);
+    let list = |index| -> Result<Vec<String>> {
+        let value: String = row.get(index)?;
+        Ok(value.lines().map(str::to_string).collect())
+    };
+
+    Ok(Article::new(row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)
+        .with_excerpt(row.get(4)?)
+        .with_image(row.get(5)?)
+        .with_published(timestamp(6)?)
+        .with_updated(timestamp(7)?)
+        .with_authors(list(8)?)
+        .with_categories(list(9)?))
+}
+
+/// Read the `HEALTH_COLUMNS` starting at column `first`
+fn health_from_row(row: &Row, first: usize) -> Result<FeedHealth> {
+    Ok(FeedHealth {
+        last_success_at: timestamp_from_row(row, first)?,
+        last_error: row.get(first + 1)?,
+        last_error_at: timestamp_from_row(row, first + 2)?,
+        consecutive_failures: row.get(first + 3)?,
+        avg_latency_ms: row.get(first + 4)?,
+    })
+}
+
+/// Turn what the user typed into an FTS5 query matching every word as a
+/// prefix. Words are quoted so FTS5 syntax in them is taken literally, except
+/// for `AND`, `OR` and `NOT` between two words.
+fn fts_query(query: &str) -> Option<String> {
+    let is_operator = |word: &str| matches!(word, "AND" | "OR" | "NOT");
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let mut parts: Vec<String> = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        if is_operator(word) {
+            let after_term = parts.last().is_some_and(|last| !is_operator(last));
+            let before_term = words.get(i + 1).is_some_and(|next| !is_operator(next));
+            if after_term && before_term {
+                parts.push(word.to_string());
+            }
+        } else {
+            parts.push(format!("\"{}\"*", word.replace('"', "\"\"")));
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// FTS5 query and oldest timestamp a saved search matches
+fn saved_search_bounds(search: &SavedSearch) -> (Option<String>, Option<i64>) {
+    (fts_query(&search.query), search.max_age_days.map(days_ago))
+}
+
+/// Unix timestamp of `days` days ago
+fn days_ago(days: u32) -> i64 {
+    Utc::now().timestamp() - i64::from(days) * 24 * 60 * 60
+}
+
+/// Split a snippet produced with the highlight markers into its parts
+fn snippet_parts(snippet: &str) -> Vec<SnippetPart> {
+    let mut parts = Vec::new();
+    for (i, run) in snippet.split(HIGHLIGHT_START).enumerate() {
+        // Every run but the first starts with a highlighted match
+        let (highlighted, plain) = match run.split_once(HIGHLIGHT_END) {
+            Some((matched, rest)) if i > 0 => (matched, rest),
+            _ => ("", run),
+        };
+        for (text, highlighted) in [(highlighted, true), (plain, false)] {
+            if !text.is_empty() {
+                parts.push(SnippetPart {
+                    text: text.to_string(),
+                    highlighted,
+                });
+            }
+        }
+    }
+    parts
+}
+
+/// Read a column of unix seconds
+fn timestamp_from_row(row: &Row, index: usize) -> Result<Option<DateTime<Utc>>> {
+    let value: Option<i64> = row.get(index)?;
+    Ok(value.and_then(|ts| DateTime::from_timestamp(ts, 0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(feed_url: &str, entry_id: &str) -> Article {
+        let link = format!("{}/{}", feed_url, entry_id);
+        Article::new(feed_url.to_string(), entry_id.to_string(), entry_id.to_string(), link)
+    }
+
+    #[test]
+    fn test_folders_nest_one_level_and_merge_timelines() {
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        let tech = db.create_folder("Tech", None).unwrap();
+        let rust = db.create_folder("Rust", Some(tech)).unwrap();
+        assert!(db.create_folder("Async", Some(rust)).is_err());
+        assert!(db.create_folder("Orphan", Some(rust + 100)).is_err());
+
+        let feeds = [
+            ("https://a.example/feed", Some(tech)),
+            ("https://b.example/feed", Some(rust)),
+            ("https://c.example/feed", None),
+        ];
+        for (url, folder_id) in feeds {
+            db.add_feed(url, url).unwrap();
+            db.set_feed_folder(url, folder_id).unwrap();
+            db.upsert_articles(url, &[article(url, "1"), article(url, "2")]).unwrap();
+        }
+        db.mark_as_viewed("https://b.example/feed", "1", "1").unwrap();
+
+        assert_eq!(db.get_folder_timeline(tech).unwrap().len(), 4);
+        assert_eq!(db.get_folder_timeline(rust).unwrap().len(), 2);
+        let unread = db.unread_counts().unwrap();
+        assert_eq!(unread["https://a.example/feed"], 2);
+        assert_eq!(unread["https://b.example/feed"], 1);
+
+        // Deleting a subfolder files its feeds in the parent
+        db.delete_folder(rust).unwrap();
+        let folder_ids: Vec<_> = db.list_feeds().unwrap().iter().map(|f| f.folder_id).collect();
+        assert_eq!(folder_ids, [Some(tech), Some(tech), None]);
+        assert_eq!(db.list_folders().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_search_ranks_and_filters_articles() {
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        let (a, b) = ("https://a.example/feed", "https://b.example/feed");
+        db.add_feed(a, "A").unwrap();
+        db.add_feed(b, "B").unwrap();
+        db.upsert_articles(a, &[
+            article(a, "1").with_excerpt(Some("Notes on rust and its borrow checker".to_string())),
+            Article::new(a.to_string(), "2".to_string(), "Rust 2024 released".to_string(), String::new()),
+        ])
+        .unwrap();
+        db.upsert_articles(b, &[article(b, "3").with_excerpt(Some("Rusty bikes".to_string()))]).unwrap();
+
+        let ids = |results: Vec<SearchResult>| results.into_iter().map(|r| r.article.id).collect::<Vec<_>>();
+        // Title matches rank first; words match as prefixes
+        let results = ids(db.search_articles("rust", None, None, false, None).unwrap());
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0], "2");
+        assert_eq!(ids(db.search_articles("rust", Some(b), None, false, None).unwrap()), ["3"]);
+        // A folder's search covers its subfolders
+        let tech = db.create_folder("Tech", None).unwrap();
+        let metal = db.create_folder("Metal", Some(tech)).unwrap();
+        db.set_feed_folder(b, Some(metal)).unwrap();
+        assert_eq!(ids(db.search_articles("rust", None, Some(tech), false, None).unwrap()), ["3"]);
+        assert_eq!(ids(db.search_articles("rust", None, Some(metal), false, None).unwrap()), ["3"]);
+        db.mark_as_viewed(a, "2", "Rust 2024 released").unwrap();
+        assert_eq!(ids(db.search_articles("rust", Some(a), None, true, None).unwrap()), ["1"]);
+        assert_eq!(ids(db.search_articles("rust checker", None, None, false, None).unwrap()), ["1"]);
+        let mut either = ids(db.search_articles("checker OR released", None, None, false, None).unwrap());
+        either.sort();
+        assert_eq!(either, ["1", "2"]);
+        assert_eq!(ids(db.search_articles("rust NOT released", Some(a), None, false, None).unwrap()), ["1"]);
+        // Other query syntax is taken literally rather than failing
+        assert_eq!(ids(db.search_articles("\"borrow AND", None, None, false, None).unwrap()), ["1"]);
+        assert_eq!(db.search_articles("NOT rust OR", None, None, false, None).unwrap().len(), 3);
+        assert!(db.search_articles("  ", None, None, false, None).unwrap().is_empty());
+
+        // Bodies are searched by their text, not their markup
+        let body = "<p>A <strong>lifetime</strong> of study</p>".to_string();
+        db.upsert_articles(b, &[article(b, "4").with_content(Some(body))]).unwrap();
+        assert_eq!(ids(db.search_articles("lifetime", None, None, false, None).unwrap()), ["4"]);
+        assert!(db.search_articles("strong", None, None, false, None).unwrap().is_empty());
+
+        let snippet = &db.search_articles("borrow", None, None, false, None).unwrap()[0].snippet;
+        let highlighted: Vec<_> = snippet.iter().filter(|p| p.highlighted).map(|p| p.text.as_str()).collect();
+        assert_eq!(highlighted, ["borrow"]);
+
+        // The index follows updates and removals
+        db.upsert_articles(b, &[article(b, "3")]).unwrap();
+        assert!(db.search_articles("bikes", None, None, false, None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_saved_searches_match_the_article_store() {
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        let tech = db.create_folder("Tech", None).unwrap();
+        let rust = db.create_folder("Rust", Some(tech)).unwrap();
+        let (a, b) = ("https://a.example/feed", "https://b.example/feed");
+        db.add_feed(a, "A").unwrap();
+        db.add_feed(b, "B").unwrap();
+        db.set_feed_folder(a, Some(rust)).unwrap();
+        let old = Utc::now() - chrono::Duration::days(30);
+        db.upsert_articles(a, &[
+            article(a, "1").with_excerpt(Some("rust and async".to_string())),
+            article(a, "2").with_excerpt(Some("rust and async".to_string())).with_published(Some(old)),
+            article(a, "3").with_excerpt(Some("rust only".to_string())),
+        ])
+        .unwrap();
+        db.upsert_articles(b, &[article(b, "4").with_excerpt(Some("async rust".to_string()))]).unwrap();
+
+        let mut search = SavedSearch {
+            id: 0,
+            name: "Async Rust".to_string(),
+            query: "rust AND async".to_string(),
+            feed_url: None,
+            folder_id: Some(rust),
+            unread_only: false,
+            max_age_days: Some(7),
+        };
+        search.id = db.create_saved_search(&search).unwrap();
+        assert_eq!(db.list_saved_searches().unwrap(), [search.clone()]);
+        let ids = |articles: Vec<Article>| articles.into_iter().map(|a| a.id).collect::<Vec<_>>();
+        assert_eq!(ids(db.get_saved_search_articles(&search).unwrap()), ["1"]);
+        assert_eq!(db.saved_search_unread_counts().unwrap()[&search.id], 1);
+
+        db.mark_as_viewed(a, "1", "1").unwrap();
+        assert_eq!(db.saved_search_unread_counts().unwrap()[&search.id], 0);
+        let unread = SavedSearch { query: String::new(), folder_id: None, unread_only: true, ..search.clone() };
+        assert_eq!(ids(db.get_saved_search_articles(&unread).unwrap()).len(), 2);
+
+        // Deleting its folder moves the search up to the parent
+        db.delete_folder(rust).unwrap();
+        assert_eq!(db.list_saved_searches().unwrap()[0].folder_id, Some(tech));
+        db.delete_saved_search(search.id).unwrap();
+        assert!(db.list_saved_searches().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_starred_articles_outlive_their_feed() {
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        let url = "https://a.example/feed";
+        db.add_feed(url, "A").unwrap();
+        let original = article(url, "1").with_excerpt(Some("First draft".to_string()));
+        db.upsert_articles(url, std::slice::from_ref(&original)).unwrap();
+
+        db.star_article(&original, "A").unwrap();
+        assert!(db.is_starred(url, "1").unwrap());
+        // The snapshot keeps what was starred, not later edits
+        let edited = original.clone().with_excerpt(Some("Edited".to_string()));
+        db.upsert_articles(url, std::slice::from_ref(&edited)).unwrap();
+        db.star_article(&edited, "A").unwrap();
+        db.remove_feed(url).unwrap();
+
+        let starred = db.list_starred().unwrap();
+        assert_eq!(starred.len(), 1);
+        assert_eq!(starred[0].feed_title, "A");
+        assert_eq!(starred[0].article.excerpt.as_deref(), Some("First draft"));
+
+        // Starred from a list, without its body, the snapshot still gets it
+        let body = "<p>The <em>whole</em> story</p>";
+        db.add_feed(url, "A").unwrap();
+        db.upsert_articles(url, &[article(url, "2").with_content(Some(body.to_string()))]).unwrap();
+        let listed = db.get_articles(url).unwrap().into_iter().find(|a| a.id == "2").unwrap();
+        assert_eq!(listed.content, None);
+        db.star_article(&listed, "A").unwrap();
+        db.conn.lock().unwrap().execute("DELETE FROM articles", []).unwrap();
+        assert_eq!(db.get_article_content(url, "2").unwrap().as_deref(), Some(body));
+
+        // Bodies stored before the current sanitizer are cleaned when read
+        db.conn
+            .lock()
+            .unwrap()
+            .execute(
+                "UPDATE starred_articles SET content = '<p>Hi <a href=\"x\">x</a><svg/onload=alert(1)></p>'",
+                [],
+            )
+            .unwrap();
+        assert_eq!(
+            db.get_article_content(url, "2").unwrap().as_deref(),
+            Some("<p>Hi <a href=\"https://a.example/feed/x\" rel=\"noopener noreferrer\">x</a></p>")
+        );
+
+        db.unstar_article(url, "1").unwrap();
+        db.unstar_article(url, "2").unwrap();
+        assert!(db.list_starred().unwrap().is_empty());
+        assert_eq!(db.get_article_content(url, "2").unwrap(), None);
+    }
+
+    #[test]
+    fn test_starred_articles_without_their_source_can_be_read() {
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        let url = "https://a.example/feed";
+        db.add_feed(url, "A").unwrap();
+        let starred = article(url, "1");
+        db.upsert_articles(url, &[starred.clone(), article(url, "2")]).unwrap();
+        db.star_article(&starred, "A").unwrap();
+        // Purged by retention, say
+        db.conn.lock().unwrap().execute("DELETE FROM articles", []).unwrap();
+
+        let keys = vec![(url.to_string(), "1".to_string()), (url.to_string(), "2".to_string())];
+        assert_eq!(db.set_articles_read(&keys, true).unwrap(), 1);
+        assert!(db.is_viewed(url, "1").unwrap());
+        assert!(!db.is_viewed(url, "2").unwrap());
+        assert_eq!(db.set_articles_read(&keys, false).unwrap(), 1);
+        assert!(!db.is_viewed(url, "1").unwrap());
+    }
+
+    #[test]
+    fn test_moved_feed_keeps_its_articles() {
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        let (old, new) = ("https://a.example/feed", "https://a.example/feed.xml");
+        db.add_feed(old, "A").unwrap();
+        db.upsert_articles(old, &[article(old, "1"), article(old, "2")]).unwrap();
+        db.star_article(&article(old, "1"), "A").unwrap();
+        db.mark_as_viewed(old, "2", "2").unwrap();
+        let search = SavedSearch {
+            id: 0,
+            name: "A".to_string(),
+            query: "news".to_string(),
+            feed_url: Some(old.to_string()),
+            folder_id: None,
+            unread_only: false,
+            max_age_days: None,
+        };
+        db.create_saved_search(&search).unwrap();
+
+        db.rename_feed(old, new).unwrap();
+        assert_eq!(db.list_feeds().unwrap()[0].url, new);
+        assert_eq!(db.get_articles(new).unwrap().len(), 2);
+        assert!(db.is_starred(new, "1").unwrap());
+        assert!(!db.is_starred(old, "1").unwrap());
+        assert!(db.is_viewed(new, "2").unwrap());
+        assert_eq!(db.list_saved_searches().unwrap()[0].feed_url.as_deref(), Some(new));
+    }
+
+    #[test]
+    fn test_retention_keeps_starred_and_current_articles() {
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        let (a, b) = ("https://a.example/feed", "https://b.example/feed");
+        db.add_feed(a, "A").unwrap();
+        let days_old = |days| Some(Utc::now() - chrono::Duration::days(days));
+        let starred = article(a, "starred").with_published(days_old(200));
+        db.upsert_articles(a, &[
+            article(a, "new1").with_published(days_old(1)),
+            article(a, "new2").with_published(days_old(2)),
+            article(a, "new3").with_published(days_old(3)),
+            article(a, "old").with_published(days_old(200)),
+            starred.clone(),
+            article(a, "current").with_published(days_old(200)),
+        ])
+        .unwrap();
+        // Only "current" was listed by the latest fetch
+        db.conn
+            .lock()
+            .unwrap()
+            .execute("UPDATE articles SET fetched_at = fetched_at - 60 WHERE entry_id != 'current'", [])
+            .unwrap();
+        db.upsert_articles(b, &[article(b, "unsubscribed")]).unwrap();
+        db.star_article(&starred, "A").unwrap();
+        db.mark_as_viewed(a, "old", "old").unwrap();
+        db.mark_as_viewed(a, "starred", "starred").unwrap();
+
+        let retention = RetentionSettings { max_age_days: 90, max_articles_per_feed: 2 };
+        assert_eq!(db.apply_retention(&retention).unwrap(), 3);
+        let mut kept: Vec<_> = db.get_articles(a).unwrap().into_iter().map(|a| a.id).collect();
+        kept.sort();
+        assert_eq!(kept, ["current", "new1", "new2", "starred"]);
+
+        let stats = db.storage_stats().unwrap();
+        assert_eq!((stats.articles, stats.starred, stats.read_markers), (4, 1, 1));
+        // The search index still matches after the file is rewritten
+        db.compact(true).unwrap();
+        assert_eq!(db.search_articles("new1", None, None, false, None).unwrap().len(), 1);
+        // Nothing left to purge
+        assert_eq!(db.clean_up(&retention, false).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_read_state_changes() {
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        let (a, b) = ("https://a.example/feed", "https://b.example/feed");
+        let old = Utc::now() - chrono::Duration::days(10);
+        for url in [a, b] {
+            db.add_feed(url, url).unwrap();
+            db.upsert_articles(url, &[article(url, "new"), article(url, "old").with_published(Some(old))])
+                .unwrap();
+        }
+        assert_eq!(db.total_unread().unwrap(), 4);
+
+        assert_eq!(db.mark_feed_read(a).unwrap(), 2);
+        assert_eq!(db.unread_counts().unwrap().get(a), None);
+        assert_eq!(db.mark_read_older_than(7).unwrap(), 1);
+        assert!(db.is_viewed(b, "old").unwrap());
+        assert!(!db.is_viewed(b, "new").unwrap());
+        assert_eq!(db.mark_all_read().unwrap(), 1);
+        assert_eq!(db.total_unread().unwrap(), 0);
+
+        db.mark_as_unread(a, "new").unwrap();
+        assert_eq!(db.unread_counts().unwrap()[a], 1);
+        let keys = vec![(b.to_string(), "new".to_string()), (b.to_string(), "old".to_string())];
+        assert_eq!(db.set_articles_read(&keys, false).unwrap(), 2);
+        assert_eq!(db.total_unread().unwrap(), 3);
+        assert_eq!(db.set_articles_read(&keys, true).unwrap(), 2);
+        assert_eq!(db.set_articles_read(&keys, true).unwrap(), 0);
+        assert_eq!(db.total_unread().unwrap(), 1);
+    }
+}
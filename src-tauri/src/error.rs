@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::opml::OpmlError;
+
+/// Why a command failed, most often fetching or storing a feed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
+pub enum FeedError {
+    /// The server couldn't be reached (DNS, connection refused, TLS, ...)
+    Network(String),
+    /// No response within the configured number of seconds
+    Timeout(u64),
+    /// The server answered with a non-success status code
+    HttpStatus(u16),
+    /// The response exceeded the size limit, in bytes
+    TooLarge(u64),
+    /// The response wasn't a valid RSS or Atom document
+    Parse(String),
+    InvalidUrl(String),
+    Database(String),
+    /// An imported subscription list couldn't be read
+    InvalidOpml(String),
+    /// HTTP settings the client can't be built with
+    InvalidSettings(String),
+    /// The settings file couldn't be written
+    Settings(String),
+}
+
+impl std::fmt::Display for FeedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeedError::Network(detail) => write!(f, "Couldn't reach the server: {}", detail),
+            FeedError::Timeout(secs) => write!(f, "The server didn't respond within {} seconds", secs),
+            FeedError::HttpStatus(code @ (401 | 403)) => {
+                write!(f, "The server refused access to this feed (HTTP {})", code)
+            }
+            FeedError::HttpStatus(404) => write!(f, "No feed was found at this address (HTTP 404)"),
+            FeedError::HttpStatus(410) => write!(f, "This feed no longer exists (HTTP 410)"),
+            FeedError::HttpStatus(429) => write!(f, "The server is limiting requests, try again later (HTTP 429)"),
+            FeedError::HttpStatus(code @ 500..=599) => {
+                write!(f, "The server ran into a problem (HTTP {})", code)
+            }
+            FeedError::HttpStatus(code) => write!(f, "The server answered with HTTP {}", code),
+            FeedError::TooLarge(limit) => {
+                write!(f, "The feed is larger than the {} MB limit", limit / (1024 * 1024))
+            }
+            FeedError::Parse(detail) => write!(f, "This address doesn't serve a valid RSS or Atom feed: {}", detail),
+            FeedError::InvalidUrl(detail) => write!(f, "Invalid feed address: {}", detail),
+            FeedError::Database(detail) => write!(f, "Couldn't update the local database: {}", detail),
+            FeedError::InvalidOpml(detail) => write!(f, "This file isn't a valid subscription list: {}", detail),
+            FeedError::InvalidSettings(detail) => write!(f, "These HTTP settings can't be used: {}", detail),
+            FeedError::Settings(detail) => write!(f, "Couldn't save the settings: {}", detail),
+        }
+    }
+}
+
+impl std::error::Error for FeedError {}
+
+impl From<rusqlite::Error> for FeedError {
+    fn from(e: rusqlite::Error) -> Self {
+        FeedError::Database(e.to_string())
+    }
+}
+
+impl From<OpmlError> for FeedError {
+    fn from(e: OpmlError) -> Self {
+        FeedError::InvalidOpml(e.to_string())
+    }
+}
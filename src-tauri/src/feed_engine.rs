@@ -1,28 +1,317 @@
 use feed_rs::parser;
-use crate::models::{Article, FeedData};
-use crate::utils::sanitize_html;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::header::{HeaderMap, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LOCATION};
+use reqwest::{StatusCode, Url};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use crate::error::FeedError;
+use crate::models::{Article, CacheValidators, FeedCandidate, FeedData, HttpSettings};
+use crate::utils::{excerpt, sanitize_html};
 
-pub async fn fetch_feed(url: String) -> Result<FeedData, String> {
-    // Fetch the RSS/Atom feed
-    let response = reqwest::get(&url)
-        .await
-        .map_err(|e| format!("Failed to fetch feed: {}", e))?;
+/// Maximum number of feeds fetched at the same time
+pub const MAX_CONCURRENT_FETCHES: usize = 8;
 
-    let content = response
-        .text()
-        .await
-        .map_err(|e| format!("Failed to read feed content: {}", e))?;
+/// Shortest connect or read timeout accepted; with none every fetch would fail
+pub const MIN_TIMEOUT_SECS: u64 = 1;
 
+/// Largest feed document we are willing to download, in bytes
+const MAX_FEED_SIZE: u64 = 10 * 1024 * 1024;
+
+/// Where sites commonly serve their feed, tried when a page doesn't link to one
+const COMMON_FEED_PATHS: [&str; 3] = ["/feed", "/rss.xml", "/atom.xml"];
+
+/// `type`s of `<link rel="alternate">` elements that point at a feed
+const FEED_LINK_TYPES: [&str; 3] = ["application/rss+xml", "application/atom+xml", "application/feed+json"];
+
+/// Result of a successful fetch
+#[derive(Debug, Clone)]
+pub enum FetchOutcome {
+    /// The feed was downloaded; `CacheValidators` are to be sent with the next fetch
+    Updated(FeedData, CacheValidators),
+    /// The server answered 304, so the stored articles are still current;
+    /// `CacheValidators` are to be sent with the next fetch
+    NotModified(CacheValidators),
+    /// The server answered 410: the feed was removed for good
+    Gone,
+}
+
+/// What a fetch found out about a feed
+#[derive(Debug, Clone)]
+pub struct FeedResponse {
+    pub outcome: FetchOutcome,
+    /// New address of a feed that permanently moved (301/308); the
+    /// subscription should be updated to it
+    pub moved_to: Option<String>,
+    /// Time from sending the request to having the feed parsed
+    pub latency: Duration,
+}
+
+/// What an address entered by the user turned out to be
+#[derive(Debug, Clone)]
+pub enum Discovery {
+    /// A feed, or a web page linking to exactly one feed. In the latter case
+    /// the feed was fetched in its place and `moved_to` points at it.
+    Feed(FeedResponse),
+    /// A web page linking to several feeds, for the user to pick from
+    Candidates(Vec<FeedCandidate>),
+}
+
+/// User-Agent sent when the settings don't override it
+pub fn default_user_agent() -> String {
+    format!("Czytaj/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Downloads feeds through one shared HTTP client, so connections are
+/// pooled across fetches. Cloning is cheap and shares the client.
+#[derive(Debug, Clone)]
+pub struct FeedEngine {
+    client: reqwest::Client,
+    connect_timeout: Duration,
+    read_timeout: Duration,
+    max_redirects: usize,
+}
+
+impl FeedEngine {
+    pub fn new(settings: &HttpSettings) -> Result<Self, FeedError> {
+        let connect_timeout = Duration::from_secs(settings.connect_timeout_secs);
+        let read_timeout = Duration::from_secs(settings.read_timeout_secs);
+        let client = reqwest::Client::builder()
+            .user_agent(settings.user_agent.clone().unwrap_or_else(default_user_agent))
+            .gzip(true)
+            .brotli(true)
+            .connect_timeout(connect_timeout)
+            .read_timeout(read_timeout)
+            // Redirects are followed by hand to notice permanent moves
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| FeedError::InvalidSettings(e.to_string()))?;
+        Ok(Self {
+            client,
+            connect_timeout,
+            read_timeout,
+            max_redirects: settings.max_redirects,
+        })
+    }
+
+    /// Fetch a feed once `limiter` has a free slot
+    pub async fn fetch_feed_limited(
+        &self,
+        limiter: Arc<Semaphore>,
+        url: String,
+        validators: CacheValidators,
+    ) -> Result<FeedResponse, FeedError> {
+        let _permit = limiter
+            .acquire_owned()
+            .await
+            .map_err(|e| FeedError::Network(e.to_string()))?;
+
+        self.fetch_feed(url, &validators).await
+    }
+
+    /// Fetch a feed, asking the server to skip the body if it hasn't changed
+    /// since the fetch that produced `validators`
+    pub async fn fetch_feed(&self, url: String, validators: &CacheValidators) -> Result<FeedResponse, FeedError> {
+        let started = Instant::now();
+        let (response, _, moved_to) = self.send(&url, validators).await?;
+
+        let outcome = match response.status() {
+            StatusCode::NOT_MODIFIED => {
+                // A 304 may renew the validators; the ones it leaves out still hold
+                let renewed = cache_validators(response.headers());
+                FetchOutcome::NotModified(CacheValidators {
+                    etag: renewed.etag.or_else(|| validators.etag.clone()),
+                    last_modified: renewed.last_modified.or_else(|| validators.last_modified.clone()),
+                })
+            }
+            StatusCode::GONE => FetchOutcome::Gone,
+            _ => {
+                let validators = cache_validators(response.headers());
+                let content = self.read_body(response).await?;
+                let feed_data = parse_feed_data(moved_to.as_deref().unwrap_or(&url), &content)?;
+                FetchOutcome::Updated(feed_data, validators)
+            }
+        };
+        Ok(FeedResponse {
+            outcome,
+            moved_to,
+            latency: started.elapsed(),
+        })
+    }
+
+    /// Fetch an address entered by the user, which may be a feed or a web
+    /// page. Pages are searched for `<link rel="alternate">` feed links,
+    /// falling back to probing `COMMON_FEED_PATHS` on the same site.
+    pub async fn discover(&self, url: String) -> Result<Discovery, FeedError> {
+        let started = Instant::now();
+        let no_cache = CacheValidators::default();
+        let (response, page_url, moved_to) = self.send(&url, &no_cache).await?;
+        if response.status() == StatusCode::GONE {
+            return Ok(Discovery::Feed(FeedResponse {
+                outcome: FetchOutcome::Gone,
+                moved_to,
+                latency: started.elapsed(),
+            }));
+        }
+
+        let validators = cache_validators(response.headers());
+        let content = self.read_body(response).await?;
+        match parse_feed_data(moved_to.as_deref().unwrap_or(&url), &content) {
+            Ok(feed_data) => {
+                return Ok(Discovery::Feed(FeedResponse {
+                    outcome: FetchOutcome::Updated(feed_data, validators),
+                    moved_to,
+                    latency: started.elapsed(),
+                }))
+            }
+            Err(e) if !looks_like_html(&content) => return Err(e),
+            Err(_) => {}
+        }
+
+        let mut candidates = advertised_feeds(&page_url, &String::from_utf8_lossy(&content));
+        if candidates.is_empty() {
+            for path in COMMON_FEED_PATHS {
+                let Ok(probe) = page_url.join(path) else {
+                    continue;
+                };
+                if let Ok(FeedResponse {
+                    outcome: FetchOutcome::Updated(feed_data, _),
+                    moved_to,
+                    ..
+                }) = self.fetch_feed(probe.to_string(), &no_cache).await
+                {
+                    let url = moved_to.unwrap_or_else(|| probe.to_string());
+                    if candidates.iter().all(|c: &FeedCandidate| c.url != url) {
+                        candidates.push(FeedCandidate {
+                            url,
+                            title: Some(feed_data.title),
+                        });
+                    }
+                }
+            }
+        }
+
+        match candidates.len() {
+            0 => Err(FeedError::Parse("the web page doesn't link to a feed".to_string())),
+            1 => {
+                let feed_url = candidates.remove(0).url;
+                let mut response = self.fetch_feed(feed_url.clone(), &no_cache).await?;
+                response.moved_to = response.moved_to.or(Some(feed_url));
+                Ok(Discovery::Feed(response))
+            }
+            _ => Ok(Discovery::Candidates(candidates)),
+        }
+    }
+
+    /// Send a GET for `url`, following redirects. Returns the final response,
+    /// the URL it came from and, if every hop was permanent, where the
+    /// resource moved to.
+    async fn send(
+        &self,
+        url: &str,
+        validators: &CacheValidators,
+    ) -> Result<(reqwest::Response, Url, Option<String>), FeedError> {
+        let mut current = Url::parse(url).map_err(|e| FeedError::InvalidUrl(e.to_string()))?;
+        let mut moved_to = None;
+        let mut only_permanent = true;
+        let mut redirects = 0;
+
+        loop {
+            let mut request = self.client.get(current.clone());
+            if let Some(etag) = &validators.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| self.request_error(e))?;
+
+            let status = response.status();
+            // Other 3xx answers, like 304, may carry a Location that isn't a redirect
+            let redirected = matches!(
+                status,
+                StatusCode::MOVED_PERMANENTLY
+                    | StatusCode::FOUND
+                    | StatusCode::SEE_OTHER
+                    | StatusCode::TEMPORARY_REDIRECT
+                    | StatusCode::PERMANENT_REDIRECT
+            );
+            let location = response.headers().get(LOCATION).and_then(|value| value.to_str().ok());
+            let Some(location) = location.filter(|_| redirected) else {
+                return Ok((response, current, moved_to));
+            };
+            if redirects == self.max_redirects {
+                return Err(FeedError::Network(format!("more than {} redirects", self.max_redirects)));
+            }
+            redirects += 1;
+            current = current
+                .join(location)
+                .map_err(|e| FeedError::InvalidUrl(format!("redirect to {}: {}", location, e)))?;
+
+            // Only a chain of permanent redirects moves the feed; once a
+            // temporary one is seen, the last permanent target is kept
+            only_permanent &= matches!(status, StatusCode::MOVED_PERMANENTLY | StatusCode::PERMANENT_REDIRECT);
+            if only_permanent {
+                moved_to = Some(current.to_string());
+            }
+        }
+    }
+
+    /// Download the body of a successful response, up to `MAX_FEED_SIZE`
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>, FeedError> {
+        if !response.status().is_success() {
+            return Err(FeedError::HttpStatus(response.status().as_u16()));
+        }
+
+        // Check the advertised size first, then keep counting in case it lied
+        if response.content_length().is_some_and(|length| length > MAX_FEED_SIZE) {
+            return Err(FeedError::TooLarge(MAX_FEED_SIZE));
+        }
+        let mut content = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| self.request_error(e))? {
+            if (content.len() + chunk.len()) as u64 > MAX_FEED_SIZE {
+                return Err(FeedError::TooLarge(MAX_FEED_SIZE));
+            }
+            content.extend_from_slice(&chunk);
+        }
+        Ok(content)
+    }
+
+    fn request_error(&self, e: reqwest::Error) -> FeedError {
+        if e.is_timeout() {
+            let timeout = if e.is_connect() { self.connect_timeout } else { self.read_timeout };
+            return FeedError::Timeout(timeout.as_secs());
+        }
+        // reqwest's own message only names the URL; the cause (DNS failure,
+        // refused connection, ...) is further down the source chain
+        let e = e.without_url();
+        let mut detail = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            detail = format!("{}: {}", detail, cause);
+            source = cause.source();
+        }
+        FeedError::Network(detail)
+    }
+}
+
+/// Turn a downloaded feed document into articles
+fn parse_feed_data(url: &str, content: &[u8]) -> Result<FeedData, FeedError> {
     // Parse the feed
-    let feed = parser::parse(content.as_bytes())
-        .map_err(|e| format!("Failed to parse feed: {}", e))?;
+    let feed = parse_feed(content)
+        .map_err(|e| FeedError::Parse(e.to_string()))?;
 
     // Extract feed title
     let feed_title = feed
         .title
         .as_ref()
         .map(|t| t.content.clone())
-        .unwrap_or_else(|| url.clone());
+        .unwrap_or_else(|| url.to_string());
 
     // Extract articles
     let articles: Vec<Article> = feed
@@ -41,23 +330,48 @@ pub async fn fetch_feed(url: String) -> Result<FeedData, String> {
                 .map(|l| l.href.clone())
                 .unwrap_or_else(|| "No link".to_string());
 
-            // Extract and sanitize excerpt from summary or content
+            // Full body from the content element, or the summary of feeds
+            // that put the whole article there
+            let body = entry
+                .content
+                .as_ref()
+                .and_then(|c| c.body.as_deref())
+                .or_else(|| entry.summary.as_ref().map(|text| text.content.as_str()));
+            let content = body
+                .map(|html| sanitize_html(html, &link))
+                .filter(|html| !html.is_empty());
+
+            // Plain-text excerpt from the summary, or the start of the body
             let excerpt = entry
                 .summary
                 .as_ref()
-                .map(|text| sanitize_html(&text.content))
-                .or_else(|| {
-                    entry.content.as_ref().and_then(|c| {
-                        c.body.as_ref().map(|body| sanitize_html(body))
-                    })
-                });
+                .map(|text| excerpt(&text.content))
+                .or_else(|| body.map(excerpt));
 
             // Extract image URL from media content or content
             let image_url = extract_image_url(entry);
 
-            Article::new(title, link)
+            // Entries without an id of their own get one derived from their content
+            let id = if entry.id.is_empty() {
+                content_id(entry)
+            } else {
+                entry.id.clone()
+            };
+
+            Article::new(url.to_string(), id, title, link)
                 .with_excerpt(excerpt)
+                .with_content(content)
                 .with_image(image_url)
+                .with_published(entry.published)
+                .with_updated(entry.updated)
+                .with_authors(entry.authors.iter().map(|a| a.name.clone()).collect())
+                .with_categories(
+                    entry
+                        .categories
+                        .iter()
+                        .map(|c| c.label.clone().unwrap_or_else(|| c.term.clone()))
+                        .collect(),
+                )
         })
         .collect();
 
@@ -67,6 +381,109 @@ pub async fn fetch_feed(url: String) -> Result<FeedData, String> {
     })
 }
 
+fn cache_validators(headers: &HeaderMap) -> CacheValidators {
+    let header = |name| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    CacheValidators {
+        etag: header(ETAG),
+        last_modified: header(LAST_MODIFIED),
+    }
+}
+
+fn looks_like_html(content: &[u8]) -> bool {
+    let start = String::from_utf8_lossy(&content[..content.len().min(1024)]).to_ascii_lowercase();
+    start.contains("<!doctype html") || start.contains("<html")
+}
+
+/// Feeds a web page advertises through `<link rel="alternate">` elements,
+/// with relative addresses resolved against `page_url`
+fn advertised_feeds(page_url: &Url, html: &str) -> Vec<FeedCandidate> {
+    static LINK_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)<link\b[^>]*>").unwrap());
+    static ATTR_REGEX: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"([a-zA-Z-]+)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#).unwrap()
+    });
+
+    let mut candidates: Vec<FeedCandidate> = Vec::new();
+    for link in LINK_REGEX.find_iter(html) {
+        let attributes: HashMap<String, String> = ATTR_REGEX
+            .captures_iter(link.as_str())
+            .map(|cap| {
+                let value = cap.get(2).or(cap.get(3)).or(cap.get(4)).map_or("", |m| m.as_str());
+                (cap[1].to_ascii_lowercase(), html_escape::decode_html_entities(value).trim().to_string())
+            })
+            .collect();
+        let attribute = |name: &str| attributes.get(name).map(String::as_str);
+
+        let alternate = attribute("rel")
+            .is_some_and(|rel| rel.split_ascii_whitespace().any(|r| r.eq_ignore_ascii_case("alternate")));
+        let feed_type = attribute("type").is_some_and(|t| {
+            let mime = t.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+            FEED_LINK_TYPES.contains(&mime.as_str())
+        });
+        let Some(href) = attribute("href").filter(|_| alternate && feed_type) else {
+            continue;
+        };
+        let Ok(url) = page_url.join(href) else {
+            continue;
+        };
+        let url = url.to_string();
+        if candidates.iter().all(|c| c.url != url) {
+            candidates.push(FeedCandidate {
+                url,
+                title: attribute("title").filter(|t| !t.is_empty()).map(str::to_string),
+            });
+        }
+    }
+    candidates
+}
+
+fn parse_feed(content: &[u8]) -> Result<feed_rs::model::Feed, parser::ParseFeedError> {
+    parser::Builder::new()
+        .id_generator(|links, title, uri| {
+            // feed-rs falls back to a random UUID when an entry has neither an
+            // id nor a link, which would give it a new identity on every fetch.
+            // Leave those empty so content_id() can assign a stable one.
+            if links.is_empty() {
+                String::new()
+            } else {
+                parser::generate_id(links, title, uri)
+            }
+        })
+        .build()
+        .parse(content)
+}
+
+/// Stable identifier for an entry that has neither an id nor a link, built
+/// from a hash of its title, dates and body
+fn content_id(entry: &feed_rs::model::Entry) -> String {
+    // FNV-1a, chosen because its output is stable across Rust releases
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut write = |bytes: &[u8]| {
+        for byte in bytes.iter().chain(std::iter::once(&0xff)) {
+            hash ^= u64::from(*byte);
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    };
+
+    write(entry.title.as_ref().map(|t| t.content.as_bytes()).unwrap_or_default());
+    write(entry.published.or(entry.updated).map(|d| d.to_rfc3339()).unwrap_or_default().as_bytes());
+    write(entry.summary.as_ref().map(|t| t.content.as_bytes()).unwrap_or_default());
+    write(
+        entry
+            .content
+            .as_ref()
+            .and_then(|c| c.body.as_deref())
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+
+    format!("content-{:016x}", hash)
+}
+
 fn extract_image_url(entry: &feed_rs::model::Entry) -> Option<String> {
     // Try to get image from media content
     if let Some(media) = entry.media.first() {
@@ -94,9 +511,6 @@ fn extract_image_url(entry: &feed_rs::model::Entry) -> Option<String> {
 }
 
 fn extract_first_image_from_html(html: &str) -> Option<String> {
-    use regex::Regex;
-    use once_cell::sync::Lazy;
-    
     static IMG_REGEX: Lazy<Regex> = Lazy::new(|| {
         Regex::new(r#"<img[^>]+src=["']([^"']+)["']"#).unwrap()
     });
@@ -106,3 +520,272 @@ fn extract_first_image_from_html(html: &str) -> Option<String> {
         .and_then(|cap| cap.get(1))
         .map(|m| m.as_str().to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_IDS: &str = r#"<?xml version="1.0"?>
+        <rss version="2.0"><channel><title>Notes</title>
+            <item><title>First</title><description>One</description></item>
+            <item><title>Second</title><description>Two</description></item>
+        </channel></rss>"#;
+
+    #[test]
+    fn test_entries_without_id_or_link_get_stable_ids() {
+        let ids = |feed: feed_rs::model::Feed| -> Vec<String> {
+            feed.entries.iter().map(content_id).collect()
+        };
+        let first = ids(parse_feed(NO_IDS.as_bytes()).unwrap());
+        let second = ids(parse_feed(NO_IDS.as_bytes()).unwrap());
+        assert_eq!(first, second);
+        assert_ne!(first[0], first[1]);
+        assert!(parse_feed(NO_IDS.as_bytes()).unwrap().entries.iter().all(|e| e.id.is_empty()));
+    }
+
+    #[test]
+    fn test_full_content_is_kept_apart_from_the_excerpt() {
+        let paragraph = format!("<p>{}</p>", "Lots of words. ".repeat(50));
+        let feed = format!(
+            r#"<?xml version="1.0"?>
+            <rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/"><channel><title>Notes</title>
+                <item><guid>1</guid><title>Long</title><description>Short summary</description>
+                    <content:encoded><![CDATA[{0}{0}<script>alert(1)</script>]]></content:encoded></item>
+                <item><guid>2</guid><title>Summary only</title><description>&lt;p&gt;Whole &lt;b&gt;post&lt;/b&gt;&lt;/p&gt;</description></item>
+            </channel></rss>"#,
+            paragraph
+        );
+        let articles = parse_feed_data("https://example.com/feed", feed.as_bytes()).unwrap().articles;
+
+        assert_eq!(articles[0].excerpt.as_deref(), Some("Short summary"));
+        let content = articles[0].content.as_deref().unwrap();
+        assert_eq!(content.matches("<p>").count(), 2);
+        assert!(content.len() > 1000);
+        assert!(!content.contains("script"));
+        assert_eq!(articles[1].content.as_deref(), Some("<p>Whole <b>post</b></p>"));
+        assert_eq!(articles[1].excerpt.as_deref(), Some("Whole post"));
+    }
+
+    #[test]
+    fn test_advertised_feeds_are_found_in_html() {
+        let page = Url::parse("https://example.com/blog/").unwrap();
+        let html = r#"<html><head>
+            <link rel="stylesheet" type="text/css" href="/style.css">
+            <link rel="alternate" type="application/rss+xml" title="Posts &amp; notes" href="feed.xml">
+            <LINK REL='alternate home' TYPE='application/atom+xml' HREF='https://feeds.example.com/atom'>
+            <link rel="alternate" type="application/feed+json" href="/feed.json" title="">
+            <link rel="alternate" type="text/html" hreflang="pl" href="/pl/">
+            <link rel="alternate" type="application/rss+xml" href="/blog/feed.xml">
+        </head></html>"#;
+
+        let candidates = advertised_feeds(&page, html);
+        let urls: Vec<&str> = candidates.iter().map(|c| c.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            [
+                "https://example.com/blog/feed.xml",
+                "https://feeds.example.com/atom",
+                "https://example.com/feed.json",
+            ]
+        );
+        assert_eq!(candidates[0].title.as_deref(), Some("Posts & notes"));
+        assert_eq!(candidates[2].title, None);
+        assert!(looks_like_html(html.as_bytes()));
+        assert!(!looks_like_html(NO_IDS.as_bytes()));
+    }
+
+    /// Answer one request per response on a local port, returning the feed
+    /// URL and a handle yielding the raw requests received
+    fn serve(responses: Vec<String>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/feed.xml", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                }
+                requests.push(String::from_utf8_lossy(&request).to_lowercase());
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_unchanged_feed_is_not_modified() {
+        let (url, server) = serve(vec![
+            format!(
+                "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nLast-Modified: Mon, 01 Jan 2024 10:00:00 GMT\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                NO_IDS.len(),
+                NO_IDS
+            ),
+            "HTTP/1.1 304 Not Modified\r\nETag: \"v2\"\r\nConnection: close\r\n\r\n".to_string(),
+        ]);
+
+        let engine = FeedEngine::new(&HttpSettings::default()).unwrap();
+        let validators = match engine.fetch_feed(url.clone(), &CacheValidators::default()).await.unwrap().outcome {
+            FetchOutcome::Updated(feed, validators) => {
+                assert_eq!(feed.articles.len(), 2);
+                validators
+            }
+            other => panic!("unexpected first fetch outcome: {:?}", other),
+        };
+        assert_eq!(validators.etag.as_deref(), Some("\"v1\""));
+
+        match engine.fetch_feed(url, &validators).await.unwrap().outcome {
+            FetchOutcome::NotModified(renewed) => {
+                assert_eq!(renewed.etag.as_deref(), Some("\"v2\""));
+                assert_eq!(renewed.last_modified, validators.last_modified);
+            }
+            other => panic!("unexpected second fetch outcome: {:?}", other),
+        }
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].contains(&format!("user-agent: {}", default_user_agent().to_lowercase())));
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+        assert!(requests[1].contains("if-modified-since: mon, 01 jan 2024 10:00:00 gmt"));
+    }
+
+    #[tokio::test]
+    async fn test_permanent_redirects_and_gone_feeds() {
+        let redirect = |status: &str, location: &str| {
+            format!("HTTP/1.1 {}\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status, location)
+        };
+        let feed = format!(
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            NO_IDS.len(),
+            NO_IDS
+        );
+        let (url, server) = serve(vec![
+            redirect("301 Moved Permanently", "/moved.xml"),
+            feed.clone(),
+            redirect("308 Permanent Redirect", "/moved.xml"),
+            redirect("302 Found", "/elsewhere.xml"),
+            feed,
+            redirect("304 Not Modified", "/elsewhere.xml"),
+            "HTTP/1.1 410 Gone\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+        ]);
+        let moved = url.replace("/feed.xml", "/moved.xml");
+        let engine = FeedEngine::new(&HttpSettings::default()).unwrap();
+        let no_cache = CacheValidators::default();
+
+        let response = engine.fetch_feed(url.clone(), &no_cache).await.unwrap();
+        assert_eq!(response.moved_to.as_deref(), Some(moved.as_str()));
+        match response.outcome {
+            FetchOutcome::Updated(feed, _) => assert!(feed.articles.iter().all(|a| a.feed_url == moved)),
+            other => panic!("unexpected outcome: {:?}", other),
+        }
+
+        // A temporary hop after a permanent one only moves the feed that far
+        let response = engine.fetch_feed(url.clone(), &no_cache).await.unwrap();
+        assert_eq!(response.moved_to.as_deref(), Some(moved.as_str()));
+
+        // Only redirect statuses are followed
+        let response = engine.fetch_feed(url.clone(), &no_cache).await.unwrap();
+        assert!(response.moved_to.is_none());
+        assert!(matches!(response.outcome, FetchOutcome::NotModified(_)));
+
+        let response = engine.fetch_feed(url, &no_cache).await.unwrap();
+        assert!(response.moved_to.is_none());
+        assert!(matches!(response.outcome, FetchOutcome::Gone));
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_discovery_on_web_pages() {
+        let ok = |body: &str| {
+            format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+        };
+        let not_found = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string();
+        let two_links = r#"<!DOCTYPE html><html><head>
+            <link rel="alternate" type="application/rss+xml" title="Posts" href="/posts.xml">
+            <link rel="alternate" type="application/atom+xml" title="Comments" href="/comments.xml">
+            </head></html>"#;
+        let (url, server) = serve(vec![
+            ok(two_links),
+            // No links: /feed and /atom.xml are probed in vain, /rss.xml is
+            // found and then fetched as the only candidate
+            ok("<html><body>Hello</body></html>"),
+            not_found.clone(),
+            ok(NO_IDS),
+            not_found,
+            ok(NO_IDS),
+        ]);
+        let engine = FeedEngine::new(&HttpSettings::default()).unwrap();
+
+        match engine.discover(url.clone()).await.unwrap() {
+            Discovery::Candidates(candidates) => {
+                let titles: Vec<_> = candidates.iter().map(|c| c.title.as_deref().unwrap()).collect();
+                assert_eq!(titles, ["Posts", "Comments"]);
+                assert_eq!(candidates[0].url, url.replace("/feed.xml", "/posts.xml"));
+            }
+            other => panic!("unexpected discovery: {:?}", other),
+        }
+
+        let rss = url.replace("/feed.xml", "/rss.xml");
+        match engine.discover(url).await.unwrap() {
+            Discovery::Feed(FeedResponse {
+                outcome: FetchOutcome::Updated(feed, _),
+                moved_to,
+                ..
+            }) => {
+                assert_eq!(moved_to.as_deref(), Some(rss.as_str()));
+                assert!(feed.articles.iter().all(|a| a.feed_url == rss));
+            }
+            other => panic!("unexpected discovery: {:?}", other),
+        }
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failures_are_classified() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+            format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", MAX_FEED_SIZE + 1),
+            "HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nnot a feed".to_string(),
+        ]);
+        let engine = FeedEngine::new(&HttpSettings::default()).unwrap();
+        let no_cache = CacheValidators::default();
+
+        let error = |result: Result<FeedResponse, FeedError>| result.unwrap_err();
+        assert_eq!(error(engine.fetch_feed(url.clone(), &no_cache).await), FeedError::HttpStatus(404));
+        assert_eq!(error(engine.fetch_feed(url.clone(), &no_cache).await), FeedError::TooLarge(MAX_FEED_SIZE));
+        assert!(matches!(error(engine.fetch_feed(url, &no_cache).await), FeedError::Parse(_)));
+        assert!(matches!(
+            error(engine.fetch_feed("not a url".to_string(), &no_cache).await),
+            FeedError::InvalidUrl(_)
+        ));
+        server.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stalled_server_times_out() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/feed.xml", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            assert!(stream.read(&mut [0; 1024]).unwrap() > 0);
+            // Start the body, then go quiet with the connection still open
+            stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n<rss").unwrap();
+            std::thread::sleep(Duration::from_secs(2));
+        });
+
+        let settings = HttpSettings { read_timeout_secs: 1, ..HttpSettings::default() };
+        let engine = FeedEngine::new(&settings).unwrap();
+        let result = engine.fetch_feed(url, &CacheValidators::default()).await;
+        assert_eq!(result.unwrap_err(), FeedError::Timeout(1));
+        server.join().unwrap();
+    }
+}
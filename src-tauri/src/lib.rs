@@ -1,73 +1,650 @@
+use chrono::Utc;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use tauri::State;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::Semaphore;
 
 mod db;
+mod error;
 mod feed_engine;
 mod models;
+mod opml;
+mod scheduler;
 mod utils;
 
 use db::ArticleDatabase;
-use models::{AppSettings, Feed, FeedData};
+use error::FeedError;
+use feed_engine::{Discovery, FeedEngine, FeedResponse, FetchOutcome};
+use models::{
+    AddFeedResult, AppSettings, Article, CacheValidators, Feed, FeedData, FeedMovedEvent, FeedStatus, FeedStatusEvent,
+    Folder, HttpSettings, ImportPlan, SavedSearch, SearchResult, StarredArticle, StorageStats,
+};
+use scheduler::RefreshScheduler;
 
 // Application state
 pub struct AppState {
     db: Arc<Mutex<ArticleDatabase>>,
     settings: Arc<Mutex<AppSettings>>,
-    feeds: Arc<Mutex<Vec<Feed>>>,
+    /// Bounds how many feeds are fetched at the same time
+    fetch_limiter: Arc<Semaphore>,
+    /// Rebuilt whenever the HTTP settings change
+    feed_engine: Arc<Mutex<FeedEngine>>,
 }
 
 // Tauri commands
 #[tauri::command]
-async fn add_feed(url: String, state: State<'_, AppState>) -> Result<FeedData, String> {
-    let feed_data = feed_engine::fetch_feed(url.clone()).await?;
+async fn add_feed(url: String, app: AppHandle, state: State<'_, AppState>) -> Result<AddFeedResult, FeedError> {
+    let engine = state.feed_engine.lock().unwrap().clone();
+    let response = match engine.discover(url.clone()).await? {
+        Discovery::Feed(response) => response,
+        Discovery::Candidates(candidates) => return Ok(AddFeedResult::Choose { candidates }),
+    };
+    let (feed_data, validators) = match response.outcome {
+        FetchOutcome::Updated(feed_data, validators) => (feed_data, validators),
+        FetchOutcome::NotModified(_) => {
+            return Err(FeedError::HttpStatus(304))
+        }
+        FetchOutcome::Gone => return Err(FeedError::HttpStatus(410)),
+    };
+    // Subscribe to where the feed lives now, or to the feed found on the page
+    let url = match response.moved_to {
+        Some(new_url) => {
+            emit_feed_moved(&app, &url, &new_url);
+            new_url
+        }
+        None => url,
+    };
     
-    // Add feed to state
-    let mut feeds = state.feeds.lock().unwrap();
-    feeds.push(Feed::new(url, feed_data.title.clone()));
+    // Persist the subscription
+    let db = state.db.lock().unwrap();
+    db.add_feed(&url, &feed_data.title)
+        .map_err(FeedError::from)?;
     
-    Ok(feed_data)
+    let feed = store_articles(&db, &url, feed_data, &validators)?;
+    Ok(AddFeedResult::Subscribed { url, feed })
+}
+
+#[tauri::command]
+async fn remove_feed(url: String, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.remove_feed(&url)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn reorder_feeds(urls: Vec<String>, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.reorder_feeds(&urls)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn list_folders(state: State<'_, AppState>) -> Result<Vec<Folder>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.list_folders()
+        .map_err(FeedError::from)
+}
+
+/// Create a folder, inside `parent_id` for a subfolder. Folders nest one level deep.
+#[tauri::command]
+async fn create_folder(name: String, parent_id: Option<i64>, state: State<'_, AppState>) -> Result<Folder, FeedError> {
+    let db = state.db.lock().unwrap();
+    let id = db.create_folder(&name, parent_id).map_err(FeedError::from)?;
+    Ok(Folder {
+        id,
+        name,
+        parent_id,
+        collapsed: false,
+    })
+}
+
+#[tauri::command]
+async fn rename_folder(id: i64, name: String, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.rename_folder(id, &name)
+        .map_err(FeedError::from)
+}
+
+/// Delete a folder; its feeds move to the parent folder and its subfolders
+/// become top-level
+#[tauri::command]
+async fn delete_folder(id: i64, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.delete_folder(id)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn set_folder_collapsed(id: i64, collapsed: bool, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.set_folder_collapsed(id, collapsed)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn set_feed_folder(url: String, folder_id: Option<i64>, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.set_feed_folder(&url, folder_id)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn get_folder_timeline(id: i64, state: State<'_, AppState>) -> Result<Vec<Article>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.get_folder_timeline(id)
+        .map_err(FeedError::from)
+}
+
+/// Unread articles per subscribed feed, keyed by feed URL
+#[tauri::command]
+async fn get_unread_counts(state: State<'_, AppState>) -> Result<HashMap<String, usize>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.unread_counts()
+        .map_err(FeedError::from)
+}
+
+/// Number of unread articles across all subscribed feeds
+#[tauri::command]
+async fn get_total_unread(state: State<'_, AppState>) -> Result<usize, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.total_unread()
+        .map_err(FeedError::from)
+}
+
+/// Mark every article of a feed as read, returning how many were unread
+#[tauri::command]
+async fn mark_feed_read(url: String, state: State<'_, AppState>) -> Result<usize, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.mark_feed_read(&url)
+        .map_err(FeedError::from)
 }
 
 #[tauri::command]
-async fn refresh_feed(url: String) -> Result<FeedData, String> {
-    feed_engine::fetch_feed(url).await
+async fn mark_all_read(state: State<'_, AppState>) -> Result<usize, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.mark_all_read()
+        .map_err(FeedError::from)
 }
 
 #[tauri::command]
-async fn get_feeds(state: State<'_, AppState>) -> Result<Vec<Feed>, String> {
-    let feeds = state.feeds.lock().unwrap();
-    Ok(feeds.clone())
+async fn mark_read_older_than(days: u32, state: State<'_, AppState>) -> Result<usize, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.mark_read_older_than(days)
+        .map_err(FeedError::from)
 }
 
 #[tauri::command]
-async fn mark_article_viewed(url: String, title: String, state: State<'_, AppState>) -> Result<(), String> {
+async fn refresh_feed(url: String, app: AppHandle, state: State<'_, AppState>) -> Result<FeedData, FeedError> {
+    let validators = {
+        let db = state.db.lock().unwrap();
+        db.get_cache_validators(&url).map_err(FeedError::from)?
+    };
+    let engine = state.feed_engine.lock().unwrap().clone();
+    let result = engine.fetch_feed(url.clone(), &validators).await;
     let db = state.db.lock().unwrap();
-    db.mark_as_viewed(&url, &title)
-        .map_err(|e| e.to_string())
+    let (url, status) = record_fetch(&app, &db, &url, result);
+    emit_feed_status(&app, &url, status.clone());
+    match status {
+        FeedStatus::Error(e) => Err(e),
+        FeedStatus::Gone => Err(FeedError::HttpStatus(410)),
+        _ => stored_feed_data(&db, &url),
+    }
 }
 
 #[tauri::command]
-async fn is_article_viewed(url: String, state: State<'_, AppState>) -> Result<bool, String> {
+async fn get_articles(feed_url: String, state: State<'_, AppState>) -> Result<Vec<Article>, FeedError> {
     let db = state.db.lock().unwrap();
-    db.is_viewed(&url)
-        .map_err(|e| e.to_string())
+    db.get_articles(&feed_url)
+        .map_err(FeedError::from)
 }
 
 #[tauri::command]
-async fn get_viewed_articles(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+async fn get_timeline(state: State<'_, AppState>) -> Result<Vec<Article>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.get_timeline()
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn refresh_all_feeds(app: AppHandle, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let feeds = {
+        let db = state.db.lock().unwrap();
+        db.list_feeds().map_err(FeedError::from)?
+    };
+
+    // Fetch in the background; progress is reported through `feed-status` events
+    let engine = state.feed_engine.lock().unwrap().clone();
+    for feed in feeds.into_iter().filter(|feed| !feed.dead) {
+        spawn_refresh(&app, &state.db, &state.fetch_limiter, engine.clone(), feed.url);
+    }
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn set_feed_refresh_interval(url: String, minutes: Option<u64>, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.set_feed_refresh_interval(&url, minutes)
+        .map_err(FeedError::from)
+}
+
+/// Dry run of importing an OPML document, for the user to review
+#[tauri::command]
+async fn preview_opml_import(contents: String, state: State<'_, AppState>) -> Result<ImportPlan, FeedError> {
+    let db = state.db.lock().unwrap();
+    let feeds = db.list_feeds().map_err(FeedError::from)?;
+    opml::plan_import(&contents, &feeds)
+        .map_err(FeedError::from)
+}
+
+/// Subscribe to the new feeds in an OPML document and start fetching them,
+/// returning what was imported and skipped
+#[tauri::command]
+async fn import_opml(contents: String, app: AppHandle, state: State<'_, AppState>) -> Result<ImportPlan, FeedError> {
+    let plan = {
+        let db = state.db.lock().unwrap();
+        let feeds = db.list_feeds().map_err(FeedError::from)?;
+        let plan = opml::plan_import(&contents, &feeds).map_err(FeedError::from)?;
+        opml::apply_import(&db, &plan).map_err(FeedError::from)?;
+        plan
+    };
+
+    let engine = state.feed_engine.lock().unwrap().clone();
+    for feed in &plan.new_feeds {
+        spawn_refresh(&app, &state.db, &state.fetch_limiter, engine.clone(), feed.url.clone());
+    }
+    Ok(plan)
+}
+
+#[tauri::command]
+async fn export_opml(state: State<'_, AppState>) -> Result<String, FeedError> {
+    let db = state.db.lock().unwrap();
+    let feeds = db.list_feeds().map_err(FeedError::from)?;
+    let folders = db.list_folders().map_err(FeedError::from)?;
+    Ok(opml::export_opml(&feeds, &folders))
+}
+
+/// Fetch and store one feed in the background, reporting progress through
+/// `feed-status` events
+fn spawn_refresh(
+    app: &AppHandle,
+    db: &Arc<Mutex<ArticleDatabase>>,
+    limiter: &Arc<Semaphore>,
+    engine: FeedEngine,
+    url: String,
+) {
+    emit_feed_status(app, &url, FeedStatus::Pending);
+    let app = app.clone();
+    let db = Arc::clone(db);
+    let limiter = Arc::clone(limiter);
+    tauri::async_runtime::spawn(async move {
+        let validators = db.lock().unwrap().get_cache_validators(&url).unwrap_or_else(|e| {
+            eprintln!("Failed to load cache validators: {}", e);
+            CacheValidators::default()
+        });
+        let result = engine.fetch_feed_limited(limiter, url.clone(), validators).await;
+        let (url, status) = {
+            let db = db.lock().unwrap();
+            record_fetch(&app, &db, &url, result)
+        };
+        emit_feed_status(&app, &url, status);
+    });
+}
+
+/// Periodically refresh feeds that are due, for as long as the app runs
+fn start_scheduler(
+    app: AppHandle,
+    db: Arc<Mutex<ArticleDatabase>>,
+    settings: Arc<Mutex<AppSettings>>,
+    limiter: Arc<Semaphore>,
+    engine: Arc<Mutex<FeedEngine>>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut scheduler = RefreshScheduler::new();
+        let mut ticker = tokio::time::interval(scheduler::TICK);
+        loop {
+            ticker.tick().await;
+
+            let (paused, interval_minutes) = {
+                let settings = settings.lock().unwrap();
+                (settings.refresh_paused, settings.refresh_interval_minutes)
+            };
+            if paused {
+                continue;
+            }
+
+            let feeds = db.lock().unwrap().list_feeds();
+            let feeds = match feeds {
+                Ok(feeds) => feeds,
+                Err(e) => {
+                    eprintln!("Failed to list feeds for background refresh: {}", e);
+                    continue;
+                }
+            };
+            let interval = Duration::from_secs(interval_minutes * 60);
+            let engine = engine.lock().unwrap().clone();
+            for url in scheduler.due_feeds(&feeds, interval, Instant::now()) {
+                spawn_refresh(&app, &db, &limiter, engine.clone(), url);
+            }
+        }
+    });
+}
+
+/// Periodically purge articles past the retention limits and tidy up the
+/// database, the first time one interval after launch
+fn start_cleanup(db: Arc<Mutex<ArticleDatabase>>, settings: Arc<Mutex<AppSettings>>) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(scheduler::CLEANUP_INTERVAL);
+        // The first tick completes immediately
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+
+            let retention = settings.lock().unwrap().retention;
+            let db = db.lock().unwrap().clone();
+            match tauri::async_runtime::spawn_blocking(move || db.clean_up(&retention, false)).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => eprintln!("Failed to clean up the database: {}", e),
+                Err(e) => eprintln!("Database clean-up stopped: {}", e),
+            }
+        }
+    });
+}
+
+/// Store the result of refreshing a subscribed feed, following it if it
+/// moved. Returns the URL the feed is known by afterwards and its new status.
+fn record_fetch(
+    app: &AppHandle,
+    db: &ArticleDatabase,
+    url: &str,
+    result: Result<FeedResponse, FeedError>,
+) -> (String, FeedStatus) {
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => {
+            record_health(db, url, Err(&e));
+            return (url.to_string(), FeedStatus::Error(e));
+        }
+    };
+    let url = match response.moved_to {
+        Some(new_url) => match db.rename_feed(url, &new_url) {
+            Ok(()) => {
+                emit_feed_moved(app, url, &new_url);
+                new_url
+            }
+            Err(e) => return (url.to_string(), FeedStatus::Error(e.into())),
+        },
+        None => url.to_string(),
+    };
+    let status = match response.outcome {
+        FetchOutcome::Updated(feed_data, validators) => match store_articles(db, &url, feed_data, &validators) {
+            Ok(_) => FeedStatus::Ok,
+            Err(e) => FeedStatus::Error(e),
+        },
+        FetchOutcome::NotModified(validators) => match db.set_cache_validators(&url, &validators) {
+            Ok(()) => FeedStatus::NotModified,
+            Err(e) => FeedStatus::Error(e.into()),
+        },
+        FetchOutcome::Gone => match db.set_feed_dead(&url, true) {
+            Ok(()) => FeedStatus::Gone,
+            Err(e) => FeedStatus::Error(e.into()),
+        },
+    };
+    match &status {
+        FeedStatus::Ok | FeedStatus::NotModified => record_health(db, &url, Ok(response.latency)),
+        FeedStatus::Gone => record_health(db, &url, Err(&FeedError::HttpStatus(410))),
+        // Failing to store what was fetched says nothing about the feed
+        _ => {}
+    }
+    (url, status)
+}
+
+/// Update a feed's health with the outcome of its latest fetch
+fn record_health(db: &ArticleDatabase, url: &str, outcome: Result<Duration, &FeedError>) {
+    let now = Utc::now();
+    let result = db.update_feed_health(url, |health| match outcome {
+        Ok(latency) => health.record_success(now, latency),
+        Err(e) => health.record_failure(now, e),
+    });
+    if let Err(e) = result {
+        eprintln!("Failed to record feed health: {}", e);
+    }
+}
+
+fn emit_feed_moved(app: &AppHandle, from: &str, to: &str) {
+    let event = FeedMovedEvent {
+        from: from.to_string(),
+        to: to.to_string(),
+    };
+    if let Err(e) = app.emit("feed-moved", event) {
+        eprintln!("Failed to emit feed move: {}", e);
+    }
+}
+
+fn emit_feed_status(app: &AppHandle, url: &str, status: FeedStatus) {
+    let event = FeedStatusEvent {
+        url: url.to_string(),
+        status,
+    };
+    if let Err(e) = app.emit("feed-status", event) {
+        eprintln!("Failed to emit feed status: {}", e);
+    }
+}
+
+/// Save freshly fetched articles and return the feed with its full stored history
+fn store_articles(
+    db: &ArticleDatabase,
+    url: &str,
+    feed_data: FeedData,
+    validators: &CacheValidators,
+) -> Result<FeedData, FeedError> {
+    db.upsert_articles(url, &feed_data.articles)
+        .map_err(FeedError::from)?;
+    db.set_cache_validators(url, validators)
+        .map_err(FeedError::from)?;
+    db.set_feed_dead(url, false)
+        .map_err(FeedError::from)?;
+    let articles = db.get_articles(url)
+        .map_err(FeedError::from)?;
+    Ok(FeedData {
+        title: feed_data.title,
+        articles,
+    })
+}
+
+/// The stored history of a feed that has not changed since its last fetch
+fn stored_feed_data(db: &ArticleDatabase, url: &str) -> Result<FeedData, FeedError> {
+    let feeds = db.list_feeds()
+        .map_err(FeedError::from)?;
+    let title = feeds
+        .into_iter()
+        .find(|feed| feed.url == url)
+        .map(|feed| feed.title)
+        .unwrap_or_else(|| url.to_string());
+    let articles = db.get_articles(url)
+        .map_err(FeedError::from)?;
+    Ok(FeedData { title, articles })
+}
+
+#[tauri::command]
+async fn get_feeds(state: State<'_, AppState>) -> Result<Vec<Feed>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.list_feeds()
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn mark_article_viewed(feed_url: String, entry_id: String, title: String, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.mark_as_viewed(&feed_url, &entry_id, &title)
+        .map_err(FeedError::from)
+}
+
+/// Full-text search over stored articles, best matches first, optionally
+/// within one feed or folder, only unread articles and only the last
+/// `max_age_days` days
+#[tauri::command]
+async fn search_articles(
+    query: String,
+    feed: Option<String>,
+    folder: Option<i64>,
+    unread_only: Option<bool>,
+    max_age_days: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SearchResult>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.search_articles(&query, feed.as_deref(), folder, unread_only.unwrap_or(false), max_age_days)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn list_saved_searches(state: State<'_, AppState>) -> Result<Vec<SavedSearch>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.list_saved_searches()
+        .map_err(FeedError::from)
+}
+
+/// Save a search to show as a smart feed, returning its ID
+#[tauri::command]
+async fn create_saved_search(search: SavedSearch, state: State<'_, AppState>) -> Result<i64, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.create_saved_search(&search)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn delete_saved_search(id: i64, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.delete_saved_search(id)
+        .map_err(FeedError::from)
+}
+
+/// Articles currently matching a saved search, newest first
+#[tauri::command]
+async fn get_saved_search_articles(id: i64, state: State<'_, AppState>) -> Result<Vec<Article>, FeedError> {
+    let db = state.db.lock().unwrap();
+    let searches = db.list_saved_searches().map_err(FeedError::from)?;
+    match searches.iter().find(|s| s.id == id) {
+        Some(search) => db.get_saved_search_articles(search).map_err(FeedError::from),
+        None => Ok(vec![]),
+    }
+}
+
+/// Unread articles matching each saved search, keyed by ID
+#[tauri::command]
+async fn get_saved_search_unread_counts(state: State<'_, AppState>) -> Result<HashMap<i64, usize>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.saved_search_unread_counts()
+        .map_err(FeedError::from)
+}
+
+/// Star an article, saving a snapshot of it that outlives the feed
+#[tauri::command]
+async fn star_article(article: Article, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    let feeds = db.list_feeds().map_err(FeedError::from)?;
+    let feed_title = feeds
+        .iter()
+        .find(|f| f.url == article.feed_url)
+        .map_or(article.feed_url.as_str(), |f| f.title.as_str());
+    db.star_article(&article, feed_title)
+        .map_err(FeedError::from)
+}
+
+/// Full body of an article as sanitized HTML; article lists leave it out
+#[tauri::command]
+async fn get_article_content(
+    feed_url: String,
+    entry_id: String,
+    state: State<'_, AppState>,
+) -> Result<Option<String>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.get_article_content(&feed_url, &entry_id)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn unstar_article(feed_url: String, entry_id: String, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.unstar_article(&feed_url, &entry_id)
+        .map_err(FeedError::from)
+}
+
+/// Starred articles, most recently starred first
+#[tauri::command]
+async fn list_starred(state: State<'_, AppState>) -> Result<Vec<StarredArticle>, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.list_starred()
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn mark_article_unread(feed_url: String, entry_id: String, state: State<'_, AppState>) -> Result<(), FeedError> {
+    let db = state.db.lock().unwrap();
+    db.mark_as_unread(&feed_url, &entry_id)
+        .map_err(FeedError::from)
+}
+
+/// Mark several `(feed_url, entry_id)` articles as read or unread, returning
+/// how many changed
+#[tauri::command]
+async fn set_articles_read(keys: Vec<(String, String)>, read: bool, state: State<'_, AppState>) -> Result<usize, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.set_articles_read(&keys, read)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn is_article_viewed(feed_url: String, entry_id: String, state: State<'_, AppState>) -> Result<bool, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.is_viewed(&feed_url, &entry_id)
+        .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn get_viewed_articles(state: State<'_, AppState>) -> Result<Vec<(String, String)>, FeedError> {
     let db = state.db.lock().unwrap();
     db.get_viewed_articles()
-        .map_err(|e| e.to_string())
+        .map_err(FeedError::from)
 }
 
 #[tauri::command]
-async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, String> {
+async fn get_storage_stats(state: State<'_, AppState>) -> Result<StorageStats, FeedError> {
+    let db = state.db.lock().unwrap();
+    db.storage_stats()
+        .map_err(FeedError::from)
+}
+
+/// Purge old articles and compact the database right away, returning the
+/// storage used afterwards
+#[tauri::command]
+async fn clean_up_storage(state: State<'_, AppState>) -> Result<StorageStats, FeedError> {
+    let retention = state.settings.lock().unwrap().retention;
+    let db = state.db.lock().unwrap().clone();
+    // Rewriting the file is slow; keep it off the async runtime
+    tauri::async_runtime::spawn_blocking(move || {
+        db.clean_up(&retention, true)?;
+        db.storage_stats()
+    })
+    .await
+    .map_err(|e| FeedError::Database(e.to_string()))?
+    .map_err(FeedError::from)
+}
+
+#[tauri::command]
+async fn get_settings(state: State<'_, AppState>) -> Result<AppSettings, FeedError> {
     let settings = state.settings.lock().unwrap();
     Ok(settings.clone())
 }
 
 #[tauri::command]
-async fn update_settings(new_settings: AppSettings, state: State<'_, AppState>) -> Result<(), String> {
+async fn update_settings(new_settings: AppSettings, state: State<'_, AppState>) -> Result<(), FeedError> {
+    // Reject HTTP settings the client can't be built with before saving them
+    let engine = FeedEngine::new(&new_settings.http)?;
+    new_settings.save()
+        .map_err(|e| FeedError::Settings(e.to_string()))?;
+    *state.feed_engine.lock().unwrap() = engine;
     let mut settings = state.settings.lock().unwrap();
     *settings = new_settings;
     Ok(())
@@ -78,21 +655,72 @@ pub fn run() {
     // Initialize database
     let db = ArticleDatabase::new().expect("Failed to initialize database");
     
+    let settings = AppSettings::load();
+    let engine = FeedEngine::new(&settings.http).unwrap_or_else(|e| {
+        eprintln!("{}; falling back to default HTTP settings", e);
+        FeedEngine::new(&HttpSettings::default()).expect("Failed to create HTTP client")
+    });
+    
     // Create application state
     let state = AppState {
         db: Arc::new(Mutex::new(db)),
-        settings: Arc::new(Mutex::new(AppSettings::default())),
-        feeds: Arc::new(Mutex::new(Vec::new())),
+        settings: Arc::new(Mutex::new(settings)),
+        fetch_limiter: Arc::new(Semaphore::new(feed_engine::MAX_CONCURRENT_FETCHES)),
+        feed_engine: Arc::new(Mutex::new(engine)),
     };
+    let db = Arc::clone(&state.db);
+    let settings = Arc::clone(&state.settings);
+    let limiter = Arc::clone(&state.fetch_limiter);
+    let engine = Arc::clone(&state.feed_engine);
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .manage(state)
+        .setup(move |app| {
+            start_cleanup(Arc::clone(&db), Arc::clone(&settings));
+            start_scheduler(app.handle().clone(), db, settings, limiter, engine);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             add_feed,
             refresh_feed,
+            get_articles,
+            get_timeline,
+            refresh_all_feeds,
+            set_feed_refresh_interval,
+            preview_opml_import,
+            import_opml,
+            export_opml,
             get_feeds,
+            remove_feed,
+            reorder_feeds,
+            list_folders,
+            create_folder,
+            rename_folder,
+            delete_folder,
+            set_folder_collapsed,
+            set_feed_folder,
+            get_folder_timeline,
+            get_unread_counts,
+            get_total_unread,
+            mark_feed_read,
+            mark_all_read,
+            mark_read_older_than,
             mark_article_viewed,
+            mark_article_unread,
+            search_articles,
+            get_article_content,
+            list_saved_searches,
+            create_saved_search,
+            delete_saved_search,
+            get_saved_search_articles,
+            get_saved_search_unread_counts,
+            get_storage_stats,
+            clean_up_storage,
+            star_article,
+            unstar_article,
+            list_starred,
+            set_articles_read,
             is_article_viewed,
             get_viewed_articles,
             get_settings,
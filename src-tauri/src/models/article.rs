@@ -1,20 +1,41 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Article {
+    /// URL of the feed this article belongs to
+    pub feed_url: String,
+    /// Entry identifier, unique within the feed. Together with `feed_url`
+    /// this is the article's identity.
+    pub id: String,
     pub title: String,
     pub link: String,
+    /// Short plain-text preview for article lists
     pub excerpt: Option<String>,
+    /// Full body as sanitized HTML, when the feed has one. Article lists
+    /// leave it out; it is loaded for the article being read.
+    pub content: Option<String>,
     pub image_url: Option<String>,
+    pub published: Option<DateTime<Utc>>,
+    pub updated: Option<DateTime<Utc>>,
+    pub authors: Vec<String>,
+    pub categories: Vec<String>,
 }
 
 impl Article {
-    pub fn new(title: String, link: String) -> Self {
+    pub fn new(feed_url: String, id: String, title: String, link: String) -> Self {
         Self {
+            feed_url,
+            id,
             title,
             link,
             excerpt: None,
+            content: None,
             image_url: None,
+            published: None,
+            updated: None,
+            authors: Vec::new(),
+            categories: Vec::new(),
         }
     }
 
@@ -23,8 +44,33 @@ impl Article {
         self
     }
 
+    pub fn with_content(mut self, content: Option<String>) -> Self {
+        self.content = content;
+        self
+    }
+
     pub fn with_image(mut self, image_url: Option<String>) -> Self {
         self.image_url = image_url;
         self
     }
+
+    pub fn with_published(mut self, published: Option<DateTime<Utc>>) -> Self {
+        self.published = published;
+        self
+    }
+
+    pub fn with_updated(mut self, updated: Option<DateTime<Utc>>) -> Self {
+        self.updated = updated;
+        self
+    }
+
+    pub fn with_authors(mut self, authors: Vec<String>) -> Self {
+        self.authors = authors;
+        self
+    }
+
+    pub fn with_categories(mut self, categories: Vec<String>) -> Self {
+        self.categories = categories;
+        self
+    }
 }
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// A named group of feeds in the sidebar. Folders nest one level deep: a
+/// folder with a parent can't have subfolders of its own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Folder {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    /// Whether the sidebar section is folded away
+    pub collapsed: bool,
+}
@@ -1,7 +1,20 @@
 pub mod article;
 pub mod feed;
+pub mod folder;
+pub mod opml;
+pub mod search;
 pub mod settings;
+pub mod starred;
+pub mod storage;
 
 pub use article::Article;
-pub use feed::{Feed, FeedData};
-pub use settings::AppSettings;
+pub use feed::{
+    AddFeedResult, CacheValidators, Feed, FeedCandidate, FeedData, FeedHealth, FeedMovedEvent, FeedStatus,
+    FeedStatusEvent,
+};
+pub use folder::Folder;
+pub use opml::{ImportPlan, InvalidOutline, OpmlFeed};
+pub use search::{SavedSearch, SearchResult, SnippetPart};
+pub use settings::{AppSettings, HttpSettings, RetentionSettings};
+pub use starred::StarredArticle;
+pub use storage::StorageStats;
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::Article;
+
+/// Snapshot of an article saved for later. It is kept as it was when
+/// starred, even after the feed drops the article or is unsubscribed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StarredArticle {
+    #[serde(flatten)]
+    pub article: Article,
+    /// Title of the feed at the time the article was starred
+    pub feed_title: String,
+    pub starred_at: DateTime<Utc>,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// Size and contents of the article database
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageStats {
+    /// Size of the database file
+    pub database_bytes: u64,
+    /// Part of the file that compacting would give back
+    pub free_bytes: u64,
+    pub articles: usize,
+    pub starred: usize,
+    /// Articles marked as read
+    pub read_markers: usize,
+}
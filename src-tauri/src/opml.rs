@@ -0,0 +1,329 @@
+use chrono::Utc;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::encoding::Decoder;
+use quick_xml::Reader;
+use reqwest::Url;
+use std::collections::HashSet;
+use crate::db::ArticleDatabase;
+use crate::models::{Feed, Folder, ImportPlan, InvalidOutline, OpmlFeed};
+
+/// Why an OPML document couldn't be read
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpmlError {
+    /// Malformed XML, at a byte offset into the document
+    Xml { position: u64, detail: String },
+    /// An `<outline>` whose attributes can't be read
+    Outline(String),
+    /// Well-formed XML without an `<opml>` element
+    NotOpml,
+}
+
+impl std::fmt::Display for OpmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpmlError::Xml { position, detail } => write!(f, "Invalid OPML at byte {}: {}", position, detail),
+            OpmlError::Outline(detail) => write!(f, "Invalid OPML outline: {}", detail),
+            OpmlError::NotOpml => write!(f, "Not an OPML document: missing <opml> element"),
+        }
+    }
+}
+
+impl std::error::Error for OpmlError {}
+
+/// Read the subscriptions from an OPML document. Outlines without an
+/// `xmlUrl` that contain other outlines are treated as folders.
+pub fn parse_opml(xml: &str) -> Result<Vec<Result<OpmlFeed, InvalidOutline>>, OpmlError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut outlines = Vec::new();
+    // One entry per open <outline>: the folder title, or None for a feed
+    let mut open: Vec<Option<String>> = Vec::new();
+    let mut seen_opml = false;
+    loop {
+        let event = reader.read_event().map_err(|e| OpmlError::Xml {
+            position: reader.error_position(),
+            detail: e.to_string(),
+        })?;
+        match event {
+            Event::Start(element) if element.name().as_ref() == b"opml" => seen_opml = true,
+            Event::Start(element) if element.name().as_ref() == b"outline" => {
+                let attributes = Outline::read(&element, reader.decoder())?;
+                let folder = match attributes.xml_url {
+                    Some(_) => {
+                        outlines.push(attributes.into_feed(&open));
+                        None
+                    }
+                    None => Some(attributes.title.unwrap_or_else(|| "Untitled folder".to_string())),
+                };
+                open.push(folder);
+            }
+            Event::Empty(element) if element.name().as_ref() == b"outline" => {
+                let attributes = Outline::read(&element, reader.decoder())?;
+                if attributes.xml_url.is_some() {
+                    outlines.push(attributes.into_feed(&open));
+                } else {
+                    outlines.push(Err(InvalidOutline {
+                        label: attributes.title.unwrap_or_else(|| "Untitled outline".to_string()),
+                        reason: "has no feed address (xmlUrl)".to_string(),
+                    }));
+                }
+            }
+            Event::End(element) if element.name().as_ref() == b"outline" => {
+                open.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_opml {
+        return Err(OpmlError::NotOpml);
+    }
+    Ok(outlines)
+}
+
+/// Sort the outlines of an OPML document into feeds to subscribe to,
+/// duplicates of `existing` subscriptions and invalid entries
+pub fn plan_import(xml: &str, existing: &[Feed]) -> Result<ImportPlan, OpmlError> {
+    // Imported addresses come out of Url normalized, so compare like with like
+    let mut seen: HashSet<String> = existing
+        .iter()
+        .map(|f| Url::parse(&f.url).map_or_else(|_| f.url.clone(), String::from))
+        .collect();
+    let mut plan = ImportPlan::default();
+    for outline in parse_opml(xml)? {
+        match outline {
+            Ok(feed) if seen.insert(feed.url.clone()) => plan.new_feeds.push(feed),
+            Ok(feed) => plan.duplicates.push(feed),
+            Err(invalid) => plan.invalid.push(invalid),
+        }
+    }
+    Ok(plan)
+}
+
+/// Subscribe to the new feeds of an import, filing them in their folders.
+/// Folders are created as needed; as Czytaj nests folders only one level
+/// deep, feeds in deeper folders go to their second-level ancestor.
+/// Returns how many feeds were added.
+pub fn apply_import(db: &ArticleDatabase, plan: &ImportPlan) -> rusqlite::Result<usize> {
+    let added = db.add_feeds(
+        plan.new_feeds
+            .iter()
+            .map(|f| (f.url.as_str(), f.title.as_deref().unwrap_or(&f.url), f.html_url.as_deref())),
+    )?;
+
+    let mut folders = db.list_folders()?;
+    for feed in plan.new_feeds.iter().filter(|f| !f.folders.is_empty()) {
+        let mut parent_id = None;
+        for name in feed.folders.iter().take(2) {
+            let existing = folders.iter().find(|f| f.parent_id == parent_id && &f.name == name);
+            let id = match existing {
+                Some(folder) => folder.id,
+                None => {
+                    let id = db.create_folder(name, parent_id)?;
+                    folders.push(Folder {
+                        id,
+                        name: name.clone(),
+                        parent_id,
+                        collapsed: false,
+                    });
+                    id
+                }
+            };
+            parent_id = Some(id);
+        }
+        db.set_feed_folder(&feed.url, parent_id)?;
+    }
+    Ok(added)
+}
+
+/// Write the subscription list as an OPML 2.0 document, with folders as
+/// nested outlines
+pub fn export_opml(feeds: &[Feed], folders: &[Folder]) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n");
+    xml.push_str("  <head>\n    <title>Czytaj subscriptions</title>\n");
+    xml.push_str(&format!("    <dateCreated>{}</dateCreated>\n  </head>\n  <body>\n", Utc::now().to_rfc2822()));
+    write_outlines(&mut xml, feeds, folders, None, 2);
+    xml.push_str("  </body>\n</opml>\n");
+    xml
+}
+
+/// Write the folders and feeds inside `parent_id` at the given indentation
+fn write_outlines(xml: &mut String, feeds: &[Feed], folders: &[Folder], parent_id: Option<i64>, depth: usize) {
+    let indent = "  ".repeat(depth);
+    for folder in folders.iter().filter(|f| f.parent_id == parent_id) {
+        let name = escape(folder.name.as_str());
+        xml.push_str(&format!("{}<outline text=\"{}\" title=\"{}\">\n", indent, name, name));
+        write_outlines(xml, feeds, folders, Some(folder.id), depth + 1);
+        xml.push_str(&format!("{}</outline>\n", indent));
+    }
+    // Feeds whose folder no longer exists are written at the top level
+    let in_parent = |feed: &&Feed| match parent_id {
+        Some(_) => feed.folder_id == parent_id,
+        None => feed.folder_id.is_none_or(|id| folders.iter().all(|f| f.id != id)),
+    };
+    for feed in feeds.iter().filter(in_parent) {
+        let title = escape(feed.title.as_str());
+        let html_url = feed
+            .html_url
+            .as_deref()
+            .map(|url| format!(" htmlUrl=\"{}\"", escape(url)))
+            .unwrap_or_default();
+        xml.push_str(&format!(
+            "{}<outline type=\"rss\" text=\"{}\" title=\"{}\" xmlUrl=\"{}\"{}/>\n",
+            indent,
+            title,
+            title,
+            escape(feed.url.as_str()),
+            html_url
+        ));
+    }
+}
+
+/// Attributes of an `<outline>` element that matter for subscriptions
+struct Outline {
+    title: Option<String>,
+    xml_url: Option<String>,
+    html_url: Option<String>,
+}
+
+impl Outline {
+    fn read(element: &BytesStart, decoder: Decoder) -> Result<Self, OpmlError> {
+        let mut outline = Outline {
+            title: None,
+            xml_url: None,
+            html_url: None,
+        };
+        let mut text = None;
+        for attribute in element.attributes() {
+            let attribute = attribute.map_err(|e| OpmlError::Outline(e.to_string()))?;
+            let value = attribute
+                .decode_and_unescape_value(decoder)
+                .map_err(|e| OpmlError::Outline(e.to_string()))?
+                .trim()
+                .to_string();
+            if value.is_empty() {
+                continue;
+            }
+            // Attribute names are matched loosely, as exporters disagree on case
+            match attribute.key.as_ref().to_ascii_lowercase().as_slice() {
+                b"title" => outline.title = Some(value),
+                b"text" => text = Some(value),
+                b"xmlurl" => outline.xml_url = Some(value),
+                b"htmlurl" => outline.html_url = Some(value),
+                _ => {}
+            }
+        }
+        outline.title = outline.title.or(text);
+        Ok(outline)
+    }
+
+    /// The feed this outline describes, nested in the `open` folders
+    fn into_feed(self, open: &[Option<String>]) -> Result<OpmlFeed, InvalidOutline> {
+        let xml_url = self.xml_url.unwrap_or_default();
+        let invalid = |reason: String| InvalidOutline {
+            label: self.title.clone().unwrap_or_else(|| xml_url.clone()),
+            reason,
+        };
+        let url = match Url::parse(&xml_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => url,
+            Ok(url) => return Err(invalid(format!("unsupported address scheme \"{}\"", url.scheme()))),
+            Err(e) => return Err(invalid(format!("invalid feed address: {}", e))),
+        };
+        Ok(OpmlFeed {
+            url: url.to_string(),
+            title: self.title,
+            html_url: self.html_url,
+            folders: open.iter().flatten().cloned().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUBSCRIPTIONS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <opml version="2.0">
+          <head><title>Exported</title></head>
+          <body>
+            <outline text="Tech">
+              <outline text="Rust">
+                <outline type="rss" text="This Week in Rust" xmlUrl="https://this-week-in-rust.org/rss.xml"
+                         htmlUrl="https://this-week-in-rust.org/"/>
+              </outline>
+              <outline type="rss" title="Q &amp; A" xmlurl="https://example.com/qa.xml"/>
+            </outline>
+            <outline type="rss" text="Already here" xmlUrl="https://example.com/feed.xml"/>
+            <outline type="rss" text="Twice" xmlUrl="https://example.com/qa.xml"/>
+            <outline type="rss" text="Local file" xmlUrl="file:///etc/passwd"/>
+            <outline type="rss" text="Broken" xmlUrl="not a url"/>
+            <outline text="Just a note"/>
+          </body>
+        </opml>"#;
+
+    #[test]
+    fn test_import_plan_sorts_outlines() {
+        let existing = vec![Feed::new("https://example.com/feed.xml".to_string(), "Feed".to_string())];
+        let plan = plan_import(SUBSCRIPTIONS, &existing).unwrap();
+
+        assert_eq!(plan.new_feeds.len(), 2);
+        let rust = &plan.new_feeds[0];
+        assert_eq!(rust.title.as_deref(), Some("This Week in Rust"));
+        assert_eq!(rust.html_url.as_deref(), Some("https://this-week-in-rust.org/"));
+        assert_eq!(rust.folders, ["Tech", "Rust"]);
+        assert_eq!(plan.new_feeds[1].title.as_deref(), Some("Q & A"));
+        assert_eq!(plan.new_feeds[1].folders, ["Tech"]);
+
+        let duplicates: Vec<_> = plan.duplicates.iter().map(|f| f.title.as_deref().unwrap()).collect();
+        assert_eq!(duplicates, ["Already here", "Twice"]);
+        let invalid: Vec<_> = plan.invalid.iter().map(|o| o.label.as_str()).collect();
+        assert_eq!(invalid, ["Local file", "Broken", "Just a note"]);
+
+        assert_eq!(plan_import("<html><body/></html>", &existing), Err(OpmlError::NotOpml));
+        assert!(matches!(plan_import("<opml><body><outline", &existing), Err(OpmlError::Xml { .. })));
+    }
+
+    #[test]
+    fn test_export_round_trips() {
+        let folder = |id, name: &str, parent_id| Folder {
+            id,
+            name: name.to_string(),
+            parent_id,
+            collapsed: false,
+        };
+        let folders = vec![folder(1, "Tech", None), folder(2, "Rust", Some(1))];
+        let mut feeds = vec![
+            Feed::new("https://example.com/feed.xml?a=1&b=2".to_string(), "Tom & \"Jerry\"".to_string()),
+            Feed::new("https://example.org/atom".to_string(), "Plain".to_string()),
+        ];
+        feeds[1].folder_id = Some(2);
+        feeds[1].html_url = Some("https://example.org/".to_string());
+
+        let plan = plan_import(&export_opml(&feeds, &folders), &[]).unwrap();
+        let imported: Vec<_> = plan
+            .new_feeds
+            .iter()
+            .map(|f| (f.url.as_str(), f.title.as_deref().unwrap(), f.folders.join("/")))
+            .collect();
+        assert_eq!(
+            imported,
+            [
+                ("https://example.org/atom", "Plain", "Tech/Rust".to_string()),
+                ("https://example.com/feed.xml?a=1&b=2", "Tom & \"Jerry\"", String::new()),
+            ]
+        );
+        assert!(plan.invalid.is_empty());
+
+        // Importing into an empty database recreates the folders and keeps
+        // each feed's website
+        let db = ArticleDatabase::open(":memory:").unwrap();
+        assert_eq!(apply_import(&db, &plan).unwrap(), 2);
+        let names: Vec<_> = db.list_folders().unwrap().into_iter().map(|f| f.name).collect();
+        assert_eq!(names, ["Rust", "Tech"]);
+        let websites: Vec<_> = db.list_feeds().unwrap().into_iter().map(|f| f.html_url).collect();
+        assert_eq!(websites, [Some("https://example.org/".to_string()), None]);
+    }
+}
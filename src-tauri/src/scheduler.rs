@@ -0,0 +1,164 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::models::Feed;
+
+/// How often the scheduler checks whether any feed is due
+pub const TICK: Duration = Duration::from_secs(30);
+
+/// Shortest refresh interval accepted, globally or for a single feed; with
+/// none, every feed would be refetched on every tick
+pub const MIN_REFRESH_INTERVAL_MINUTES: u64 = 1;
+
+/// How often old articles are purged and the database is tidied up
+pub const CLEANUP_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Longest a failing feed is backed off to, unless its own interval is longer
+const MAX_BACKOFF: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Decides when each feed is due for a background refresh.
+///
+/// Every refresh is followed by the feed's interval (its own override or the
+/// global setting) plus up to ±10% random jitter, so feeds added together
+/// drift apart instead of hitting the network at the same moment. The
+/// interval doubles with each consecutive failure of the feed.
+#[derive(Debug, Default)]
+pub struct RefreshScheduler {
+    last_run: HashMap<String, LastRun>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LastRun {
+    at: Instant,
+    /// Fraction of the interval to wait before the next run
+    jitter: f64,
+}
+
+impl RefreshScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the URLs of feeds due at `now` and mark them as run.
+    /// Feeds seen for the first time are spread over the first tenth of their
+    /// interval rather than all refreshed at once.
+    pub fn due_feeds(&mut self, feeds: &[Feed], default_interval: Duration, now: Instant) -> Vec<String> {
+        // Forget feeds that were unsubscribed
+        self.last_run.retain(|url, _| feeds.iter().any(|f| &f.url == url));
+
+        let mut due = Vec::new();
+        for feed in feeds.iter().filter(|feed| !feed.dead) {
+            // Overrides are clamped when saved, but older databases may hold 0
+            let interval = feed
+                .refresh_interval_minutes
+                .map(|minutes| Duration::from_secs(minutes.max(MIN_REFRESH_INTERVAL_MINUTES) * 60))
+                .unwrap_or(default_interval);
+
+            match self.last_run.get(&feed.url) {
+                None => {
+                    let first = LastRun { at: now, jitter: random_fraction() * 0.1 };
+                    self.last_run.insert(feed.url.clone(), first);
+                }
+                // The backoff is worked out on every tick so a failure
+                // reported after the feed was last run still counts
+                Some(last) if now >= last.at + backoff(interval, feed.health.consecutive_failures).mul_f64(last.jitter) => {
+                    due.push(feed.url.clone());
+                    let next = LastRun { at: now, jitter: 0.9 + random_fraction() * 0.2 };
+                    self.last_run.insert(feed.url.clone(), next);
+                }
+                Some(_) => {}
+            }
+        }
+        due
+    }
+}
+
+/// Interval of a feed after `failures` consecutive failed refreshes
+fn backoff(interval: Duration, failures: u32) -> Duration {
+    if failures == 0 {
+        return interval;
+    }
+    interval
+        .saturating_mul(1 << failures.min(16))
+        .min(MAX_BACKOFF.max(interval))
+}
+
+/// Random number between zero and one
+fn random_fraction() -> f64 {
+    // RandomState is seeded randomly, which is all the randomness we need here
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(Instant::now().elapsed().as_nanos());
+    hasher.finish() as f64 / u64::MAX as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(url: &str, refresh_interval_minutes: Option<u64>) -> Feed {
+        let mut feed = Feed::new(url.to_string(), url.to_string());
+        feed.refresh_interval_minutes = refresh_interval_minutes;
+        feed
+    }
+
+    #[test]
+    fn test_feeds_are_due_once_per_interval() {
+        let mut scheduler = RefreshScheduler::new();
+        let feeds = vec![feed("a", None)];
+        let interval = Duration::from_secs(600);
+        let start = Instant::now();
+
+        assert!(scheduler.due_feeds(&feeds, interval, start).is_empty());
+        assert_eq!(scheduler.due_feeds(&feeds, interval, start + interval / 10), vec!["a"]);
+        // Next refresh lands within ±10% of the interval
+        let after = start + interval / 10;
+        assert!(scheduler.due_feeds(&feeds, interval, after + interval * 8 / 10).is_empty());
+        assert_eq!(scheduler.due_feeds(&feeds, interval, after + interval * 11 / 10), vec!["a"]);
+    }
+
+    #[test]
+    fn test_per_feed_interval_overrides_default() {
+        let mut scheduler = RefreshScheduler::new();
+        let feeds = vec![feed("fast", Some(1)), feed("slow", None)];
+        let interval = Duration::from_secs(3600);
+        let start = Instant::now();
+
+        scheduler.due_feeds(&feeds, interval, start);
+        let first = start + Duration::from_secs(400);
+        assert_eq!(scheduler.due_feeds(&feeds, interval, first), vec!["fast", "slow"]);
+        let second = first + Duration::from_secs(80);
+        assert_eq!(scheduler.due_feeds(&feeds, interval, second), vec!["fast"]);
+
+        // An override of 0 waits the shortest interval, not a single tick
+        let mut scheduler = RefreshScheduler::new();
+        let feeds = vec![feed("zero", Some(0))];
+        scheduler.due_feeds(&feeds, interval, start);
+        let first = start + Duration::from_secs(10);
+        assert_eq!(scheduler.due_feeds(&feeds, interval, first), vec!["zero"]);
+        assert!(scheduler.due_feeds(&feeds, interval, first + TICK).is_empty());
+    }
+
+    #[test]
+    fn test_failing_feeds_back_off() {
+        let mut scheduler = RefreshScheduler::new();
+        let mut feeds = vec![feed("a", None)];
+        let interval = Duration::from_secs(600);
+        let start = Instant::now();
+
+        scheduler.due_feeds(&feeds, interval, start);
+        let first = start + interval / 10;
+        assert_eq!(scheduler.due_feeds(&feeds, interval, first), vec!["a"]);
+
+        // Three failures in a row wait eight intervals
+        feeds[0].health.consecutive_failures = 3;
+        assert!(scheduler.due_feeds(&feeds, interval, first + interval * 7).is_empty());
+        assert_eq!(scheduler.due_feeds(&feeds, interval, first + interval * 9), vec!["a"]);
+
+        // ...but never longer than a day
+        let last = first + interval * 9;
+        feeds[0].health.consecutive_failures = 30;
+        assert_eq!(scheduler.due_feeds(&feeds, interval, last + MAX_BACKOFF * 11 / 10), vec!["a"]);
+    }
+}
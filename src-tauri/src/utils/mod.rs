@@ -1,3 +1,3 @@
 pub mod sanitizer;
 
-pub use sanitizer::sanitize_html;
+pub use sanitizer::{excerpt, html_to_text, sanitize_html};
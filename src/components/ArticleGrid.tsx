@@ -1,18 +1,33 @@
 import React from 'react';
-import type { Article } from '../types';
+import type { Article, Feed, StarredArticle } from '../types';
+import { articleKey } from '../services/api';
+import { byline } from '../services/format';
 
 interface ArticleGridProps {
   articles: Article[];
+  feeds: Feed[];
   viewedArticles: Set<string>;
+  /** Keys of the articles picked for bulk actions */
+  selectedArticles: Set<string>;
+  starredArticles: Set<string>;
   onArticleClick: (article: Article) => void;
+  onToggleRead: (article: Article) => void;
+  onToggleStar: (article: Article) => void;
+  onToggleSelected: (article: Article) => void;
   showImages: boolean;
   showExcerpts: boolean;
 }
 
 export function ArticleGrid({
   articles,
+  feeds,
   viewedArticles,
+  selectedArticles,
+  starredArticles,
   onArticleClick,
+  onToggleRead,
+  onToggleStar,
+  onToggleSelected,
   showImages,
   showExcerpts,
 }: ArticleGridProps) {
@@ -30,29 +45,62 @@ export function ArticleGrid({
 
   return (
     <div className="articles-grid">
-      {articles.map((article, index) => (
-        <div
-          key={index}
-          className={`article-card ${
-            viewedArticles.has(article.link) ? 'viewed' : ''
-          }`}
-          onClick={() => onArticleClick(article)}
-        >
-          {showImages && article.image_url && (
-            <img
-              src={article.image_url}
-              alt={article.title}
-              className="article-image"
-            />
-          )}
-          <div className="article-content">
-            <h3 className="article-title">{article.title}</h3>
-            {showExcerpts && article.excerpt && (
-              <p className="article-excerpt">{article.excerpt}</p>
+      {articles.map((article) => {
+        const key = articleKey(article.feed_url, article.id);
+        const viewed = viewedArticles.has(key);
+        return (
+          <div
+            key={key}
+            className={`article-card ${viewed ? 'viewed' : ''} ${selectedArticles.has(key) ? 'selected' : ''}`}
+            onClick={() => onArticleClick(article)}
+          >
+            <div className="article-card-actions" onClick={(e) => e.stopPropagation()}>
+              <input
+                type="checkbox"
+                title="Select"
+                checked={selectedArticles.has(key)}
+                onChange={() => onToggleSelected(article)}
+              />
+              <button
+                className="feed-action-btn"
+                title={starredArticles.has(key) ? 'Unstar' : 'Star'}
+                onClick={() => onToggleStar(article)}
+              >
+                {starredArticles.has(key) ? '★' : '☆'}
+              </button>
+              <button
+                className="feed-action-btn"
+                title={viewed ? 'Mark as unread' : 'Mark as read'}
+                onClick={() => onToggleRead(article)}
+              >
+                {viewed ? '○ Unread' : '● Read'}
+              </button>
+            </div>
+            {showImages && article.image_url && (
+              <img
+                src={article.image_url}
+                alt={article.title}
+                className="article-image"
+              />
             )}
+            <div className="article-content">
+              <h3 className="article-title">{article.title}</h3>
+              <div className="article-meta">
+                {[
+                  // Starred snapshots remember their feed after it is unsubscribed
+                  feeds.find((f) => f.url === article.feed_url)?.title ?? (article as Partial<StarredArticle>).feed_title,
+                  byline(article),
+                ]
+                  .filter(Boolean)
+                  .join(' · ')}
+              </div>
+              {showExcerpts && article.excerpt && (
+                <p className="article-excerpt">{article.excerpt}</p>
+              )}
+            </div>
           </div>
-        </div>
-      ))}
+        );
+      })}
     </div>
   );
 }
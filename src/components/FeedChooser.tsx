@@ -0,0 +1,32 @@
+import React from 'react';
+import type { FeedCandidate } from '../types';
+
+interface FeedChooserProps {
+  candidates: FeedCandidate[];
+  onChoose: (url: string) => void;
+  onCancel: () => void;
+}
+
+/** Lets the user pick which of the feeds linked from a web page to subscribe to */
+export function FeedChooser({ candidates, onChoose, onCancel }: FeedChooserProps) {
+  return (
+    <div className="settings-page">
+      <button className="back-button" onClick={onCancel}>
+        ← Cancel
+      </button>
+
+      <h2 className="settings-title">Choose a Feed</h2>
+
+      <p className="settings-section health-card-detail">
+        This page offers {candidates.length} feeds. Pick one to subscribe to.
+      </p>
+
+      {candidates.map((candidate) => (
+        <button key={candidate.url} className="feed-candidate" onClick={() => onChoose(candidate.url)}>
+          <div className="health-card-title">{candidate.title ?? candidate.url}</div>
+          <div className="health-card-detail">{candidate.url}</div>
+        </button>
+      ))}
+    </div>
+  );
+}
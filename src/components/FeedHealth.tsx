@@ -0,0 +1,71 @@
+import React from 'react';
+import type { Feed } from '../types';
+import { formatRelative } from '../services/format';
+
+interface FeedHealthProps {
+  feeds: Feed[];
+  onRemoveFeed: (url: string) => void;
+  onBack: () => void;
+}
+
+/** Lists feeds that are gone or failing to refresh, so they can be pruned */
+export function FeedHealth({ feeds, onRemoveFeed, onBack }: FeedHealthProps) {
+  const broken = feeds.filter((f) => f.dead || f.health.consecutive_failures > 0);
+
+  return (
+    <div className="settings-page">
+      <button className="back-button" onClick={onBack}>
+        ← Back
+      </button>
+
+      <h2 className="settings-title">Feed Health</h2>
+
+      <p className="settings-section health-card-detail">
+        {broken.length === 0
+          ? 'All feeds are refreshing normally.'
+          : `${broken.length} of ${feeds.length} feeds need attention.`}
+      </p>
+
+      {broken.map((feed) => (
+        <div key={feed.url} className="health-card">
+          <div className="health-card-body">
+            <div className="health-card-title">{feed.title}</div>
+            <div className="health-card-detail">{feed.url}</div>
+            <div className="health-card-state">{healthState(feed)}</div>
+            <div className="health-card-detail">{lastError(feed)}</div>
+            <div className="health-card-detail">{lastSuccess(feed)}</div>
+          </div>
+          <button className="btn btn-primary" onClick={() => onRemoveFeed(feed.url)}>
+            Remove
+          </button>
+        </div>
+      ))}
+    </div>
+  );
+}
+
+function healthState(feed: Feed): string {
+  if (feed.dead) {
+    return '⛔ Removed by its publisher (HTTP 410)';
+  }
+  const failures = feed.health.consecutive_failures;
+  return `⚠ ${failures} failed refresh${failures === 1 ? '' : 'es'} in a row`;
+}
+
+function lastError({ health }: Feed): string {
+  if (!health.last_error) {
+    return 'No errors recorded';
+  }
+  return health.last_error_at
+    ? `Last error ${formatRelative(health.last_error_at)}: ${health.last_error}`
+    : `Last error: ${health.last_error}`;
+}
+
+function lastSuccess({ health }: Feed): string {
+  const success = health.last_success_at
+    ? `Last successful refresh ${formatRelative(health.last_success_at)}`
+    : 'Never refreshed successfully';
+  const latency =
+    health.avg_latency_ms === null ? '' : ` · responds in ${Math.round(health.avg_latency_ms)} ms on average`;
+  return success + latency;
+}
@@ -0,0 +1,45 @@
+import React from 'react';
+import type { Article, Feed, SearchResult } from '../types';
+import { articleKey } from '../services/api';
+
+interface SearchResultsProps {
+  results: SearchResult[];
+  feeds: Feed[];
+  viewedArticles: Set<string>;
+  onArticleClick: (article: Article) => void;
+}
+
+/** Search hits, best first, with the matched words highlighted in each snippet */
+export function SearchResults({ results, feeds, viewedArticles, onArticleClick }: SearchResultsProps) {
+  if (results.length === 0) {
+    return (
+      <div className="empty-state">
+        <div className="empty-state-icon">🔍</div>
+        <div className="empty-state-title">No Matching Articles</div>
+      </div>
+    );
+  }
+
+  return (
+    <div className="search-results">
+      <div className="search-results-count">
+        {results.length === 1 ? '1 matching article' : `${results.length} matching articles`}
+      </div>
+      {results.map((result) => (
+        <div
+          key={articleKey(result.feed_url, result.id)}
+          className={`search-result ${viewedArticles.has(articleKey(result.feed_url, result.id)) ? 'viewed' : ''}`}
+          onClick={() => onArticleClick(result)}
+        >
+          <h3 className="article-title">{result.title}</h3>
+          <div className="article-meta">{feeds.find((f) => f.url === result.feed_url)?.title ?? result.feed_url}</div>
+          <p className="article-excerpt">
+            {result.snippet.map((part, index) =>
+              part.highlighted ? <mark key={index}>{part.text}</mark> : <span key={index}>{part.text}</span>
+            )}
+          </p>
+        </div>
+      ))}
+    </div>
+  );
+}
@@ -1,13 +1,59 @@
-import React from 'react';
-import type { AppSettings, Theme } from '../types';
+import React, { useEffect, useState } from 'react';
+import type { AppSettings, ImportPlan, StorageStats, Theme } from '../types';
+import { api } from '../services/api';
+import { describeError, REFRESH_INTERVALS } from '../services/format';
 
 interface SettingsProps {
   settings: AppSettings;
   onUpdateSettings: (settings: AppSettings) => void;
+  onFeedsImported: () => void;
   onBack: () => void;
 }
 
-export function Settings({ settings, onUpdateSettings, onBack }: SettingsProps) {
+const RETENTION_AGES: [number, string][] = [
+  [30, 'Keep articles 30 days'],
+  [90, 'Keep articles 90 days'],
+  [180, 'Keep articles 180 days'],
+  [365, 'Keep articles 365 days'],
+  [0, 'Keep articles forever'],
+];
+
+const RETENTION_COUNTS: [number, string][] = [
+  [100, 'At most 100 per feed'],
+  [500, 'At most 500 per feed'],
+  [1000, 'At most 1000 per feed'],
+  [5000, 'At most 5000 per feed'],
+  [0, 'No limit per feed'],
+];
+
+/** Size in bytes as a short human-readable string, e.g. "12.3 MB" */
+function formatBytes(bytes: number): string {
+  const units = ['KB', 'MB', 'GB', 'TB'];
+  if (bytes < 1024) {
+    return `${bytes} B`;
+  }
+  let size = bytes / 1024;
+  let unit = 0;
+  while (size >= 1024 && unit < units.length - 1) {
+    size /= 1024;
+    unit += 1;
+  }
+  return `${size.toFixed(1)} ${units[unit]}`;
+}
+
+export function Settings({ settings, onUpdateSettings, onFeedsImported, onBack }: SettingsProps) {
+  // OPML document picked for import and the dry run of importing it
+  const [opml, setOpml] = useState<string | null>(null);
+  const [importPlan, setImportPlan] = useState<ImportPlan | null>(null);
+  const [opmlStatus, setOpmlStatus] = useState<string | null>(null);
+  const [storage, setStorage] = useState<StorageStats | null>(null);
+
+  useEffect(() => {
+    api.getStorageStats().then(setStorage, (error) => {
+      console.error('Failed to read database size:', error);
+    });
+  }, []);
+
   const handleThemeChange = (theme: Theme) => {
     onUpdateSettings({ ...settings, theme });
   };
@@ -20,6 +66,74 @@ export function Settings({ settings, onUpdateSettings, onBack }: SettingsProps)
     onUpdateSettings({ ...settings, show_excerpts: !settings.show_excerpts });
   };
 
+  const handleRefreshIntervalChange = (minutes: number) => {
+    onUpdateSettings({ ...settings, refresh_interval_minutes: minutes });
+  };
+
+  const handleTogglePaused = () => {
+    onUpdateSettings({ ...settings, refresh_paused: !settings.refresh_paused });
+  };
+
+  const handleRetentionChange = (retention: Partial<AppSettings['retention']>) => {
+    onUpdateSettings({ ...settings, retention: { ...settings.retention, ...retention } });
+  };
+
+  const handleCleanUp = async () => {
+    try {
+      setStorage(await api.cleanUpStorage());
+    } catch (error) {
+      console.error('Failed to clean up storage:', error);
+    }
+  };
+
+  const handleOpmlFile = async (file: File | undefined) => {
+    if (!file) {
+      return;
+    }
+    try {
+      const contents = await file.text();
+      setImportPlan(await api.previewOpmlImport(contents));
+      setOpml(contents);
+      setOpmlStatus(null);
+    } catch (error) {
+      setImportPlan(null);
+      setOpmlStatus(describeError(error));
+    }
+  };
+
+  const handleConfirmImport = async () => {
+    if (opml === null) {
+      return;
+    }
+    try {
+      const plan = await api.importOpml(opml);
+      setOpmlStatus(`Imported ${plan.new_feeds.length} feeds`);
+      onFeedsImported();
+    } catch (error) {
+      setOpmlStatus(`Failed to import feeds: ${describeError(error)}`);
+    }
+    setImportPlan(null);
+    setOpml(null);
+  };
+
+  const handleCancelImport = () => {
+    setImportPlan(null);
+    setOpml(null);
+  };
+
+  const handleExport = async () => {
+    try {
+      const contents = await api.exportOpml();
+      const link = document.createElement('a');
+      link.href = URL.createObjectURL(new Blob([contents], { type: 'text/x-opml' }));
+      link.download = 'czytaj-subscriptions.opml';
+      link.click();
+      URL.revokeObjectURL(link.href);
+    } catch (error) {
+      setOpmlStatus(`Failed to export feeds: ${describeError(error)}`);
+    }
+  };
+
   return (
     <div className="settings-page">
       <button className="back-button" onClick={onBack}>
@@ -67,6 +181,130 @@ export function Settings({ settings, onUpdateSettings, onBack }: SettingsProps)
           <span className="checkbox-text">Show article excerpts</span>
         </label>
       </div>
+
+      <div className="settings-section">
+        <label className="settings-label">Background refresh</label>
+        <select
+          className="settings-select"
+          value={settings.refresh_interval_minutes}
+          onChange={(e) => handleRefreshIntervalChange(Number(e.target.value))}
+        >
+          {REFRESH_INTERVALS.map(([minutes, label]) => (
+            <option key={minutes} value={minutes}>
+              {label}
+            </option>
+          ))}
+        </select>
+
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={settings.refresh_paused}
+            onChange={handleTogglePaused}
+            className="checkbox"
+          />
+          <span className="checkbox-text">Pause automatic refresh</span>
+        </label>
+      </div>
+
+      <div className="settings-section">
+        <label className="settings-label">Storage</label>
+        <p className="health-card-detail">
+          Articles past these limits are purged every few hours; starred articles are always kept
+        </p>
+        <select
+          className="settings-select"
+          value={settings.retention.max_age_days}
+          onChange={(e) => handleRetentionChange({ max_age_days: Number(e.target.value) })}
+        >
+          {RETENTION_AGES.map(([days, label]) => (
+            <option key={days} value={days}>
+              {label}
+            </option>
+          ))}
+        </select>
+        <select
+          className="settings-select"
+          value={settings.retention.max_articles_per_feed}
+          onChange={(e) => handleRetentionChange({ max_articles_per_feed: Number(e.target.value) })}
+        >
+          {RETENTION_COUNTS.map(([count, label]) => (
+            <option key={count} value={count}>
+              {label}
+            </option>
+          ))}
+        </select>
+        {storage && (
+          <p className="health-card-detail">
+            Database: {formatBytes(storage.database_bytes)} ({formatBytes(storage.free_bytes)} reclaimable) ·{' '}
+            {storage.articles} articles · {storage.starred} starred · {storage.read_markers} marked read
+          </p>
+        )}
+        <div className="theme-buttons">
+          <button className="theme-btn" onClick={handleCleanUp}>
+            Clean up now
+          </button>
+        </div>
+      </div>
+
+      <div className="settings-section">
+        <label className="settings-label">Subscriptions (OPML)</label>
+        <div className="theme-buttons">
+          <label className="theme-btn">
+            Import…
+            <input
+              type="file"
+              accept=".opml,.xml,text/x-opml,application/xml"
+              hidden
+              onChange={(e) => {
+                handleOpmlFile(e.target.files?.[0]);
+                e.target.value = '';
+              }}
+            />
+          </label>
+          <button className="theme-btn" onClick={handleExport}>
+            Export
+          </button>
+        </div>
+
+        {opmlStatus && <p className="health-card-detail">{opmlStatus}</p>}
+
+        {importPlan && (
+          <div className="import-report">
+            <p className="health-card-title">
+              {importPlan.new_feeds.length} new, {importPlan.duplicates.length} already subscribed,{' '}
+              {importPlan.invalid.length} invalid
+            </p>
+            <ul>
+              {importPlan.new_feeds.map((feed) => (
+                <li key={feed.url}>
+                  + {[...feed.folders, feed.title ?? feed.url].join(' / ')}
+                </li>
+              ))}
+              {importPlan.duplicates.map((feed, index) => (
+                <li key={`duplicate-${index}`} className="health-card-detail">
+                  = {feed.title ?? feed.url} (already subscribed)
+                </li>
+              ))}
+              {importPlan.invalid.map((outline, index) => (
+                <li key={`invalid-${index}`} className="health-card-state">
+                  ✕ {outline.label}: {outline.reason}
+                </li>
+              ))}
+            </ul>
+            <div className="theme-buttons">
+              {importPlan.new_feeds.length > 0 && (
+                <button className="theme-btn active" onClick={handleConfirmImport}>
+                  Import feeds
+                </button>
+              )}
+              <button className="theme-btn" onClick={handleCancelImport}>
+                {importPlan.new_feeds.length > 0 ? 'Cancel' : 'Close report'}
+              </button>
+            </div>
+          </div>
+        )}
+      </div>
     </div>
   );
 }
@@ -1,24 +1,98 @@
 import React, { useState } from 'react';
-import type { Feed } from '../types';
+import type { Feed, FeedStatus, Folder, SavedSearch } from '../types';
+import { describeError, REFRESH_INTERVALS } from '../services/format';
 
 interface SidebarProps {
   feeds: Feed[];
+  folders: Folder[];
+  savedSearches: SavedSearch[];
   isCollapsed: boolean;
   onAddFeed: (url: string) => void;
-  onRefreshFeed: (url: string) => void;
+  selectedFeed: string | null;
+  selectedFolder: number | null;
+  showStarred: boolean;
+  starredCount: number;
+  selectedSavedSearch: number | null;
+  feedStatus: Record<string, FeedStatus>;
+  unreadCounts: Record<string, number>;
+  savedSearchUnread: Record<number, number>;
+  totalUnread: number;
+  onShowAllArticles: () => void;
+  onShowStarred: () => void;
+  onRefreshAll: () => void;
+  onSelectFeed: (url: string) => void;
+  onRemoveFeed: (url: string) => void;
+  onMoveFeed: (url: string, offset: number) => void;
+  onMarkFeedRead: (url: string) => void;
+  onMarkAllRead: () => void;
+  onSelectFolder: (id: number) => void;
+  onToggleFolder: (folder: Folder) => void;
+  onCreateFolder: (parentId: number | null) => void;
+  onRenameFolder: (folder: Folder) => void;
+  onDeleteFolder: (id: number) => void;
+  onMoveFeedToFolder: (url: string, folderId: number | null) => void;
+  onSetFeedRefreshInterval: (url: string, minutes: number | null) => void;
+  onSelectSavedSearch: (id: number) => void;
+  onDeleteSavedSearch: (id: number) => void;
   onOpenSettings: () => void;
+  onOpenFeedHealth: () => void;
   loading: boolean;
 }
 
 export function Sidebar({
   feeds,
+  folders,
+  savedSearches,
   isCollapsed,
   onAddFeed,
-  onRefreshFeed,
+  selectedFeed,
+  selectedFolder,
+  showStarred,
+  starredCount,
+  selectedSavedSearch,
+  feedStatus,
+  unreadCounts,
+  savedSearchUnread,
+  totalUnread,
+  onShowAllArticles,
+  onShowStarred,
+  onRefreshAll,
+  onSelectFeed,
+  onRemoveFeed,
+  onMoveFeed,
+  onMarkFeedRead,
+  onMarkAllRead,
+  onSelectFolder,
+  onToggleFolder,
+  onCreateFolder,
+  onRenameFolder,
+  onDeleteFolder,
+  onMoveFeedToFolder,
+  onSetFeedRefreshInterval,
+  onSelectSavedSearch,
+  onDeleteSavedSearch,
   onOpenSettings,
+  onOpenFeedHealth,
   loading,
 }: SidebarProps) {
   const [feedInput, setFeedInput] = useState('');
+  const failing = feeds.filter((f) => f.dead || f.health.consecutive_failures > 0).length;
+  const folderIds = new Set(folders.map((f) => f.id));
+  const topLevel = folders.filter((f) => f.parent_id === null);
+  const subfolders = (id: number) => folders.filter((f) => f.parent_id === id);
+  // Feeds whose folder no longer exists are listed with the unfiled ones
+  const unfiled = feeds.filter((f) => f.folder_id === null || !folderIds.has(f.folder_id));
+  const folderUnread = (id: number) => {
+    const ids = [id, ...subfolders(id).map((f) => f.id)];
+    return feeds
+      .filter((f) => f.folder_id !== null && ids.includes(f.folder_id))
+      .reduce((sum, f) => sum + (unreadCounts[f.url] ?? 0), 0);
+  };
+  // Subfolders can only go inside top-level folders
+  const selected = folders.find((f) => f.id === selectedFolder);
+  const newFolderParent = selected && selected.parent_id === null ? selected.id : null;
+  const showsAll =
+    selectedFeed === null && selectedFolder === null && selectedSavedSearch === null && !showStarred;
 
   const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault();
@@ -35,7 +109,7 @@ export function Sidebar({
         <form onSubmit={handleSubmit} className="feed-input-container">
           <input
             type="url"
-            placeholder="Enter RSS/Atom feed URL"
+            placeholder="Enter feed or website URL"
             value={feedInput}
             onChange={(e) => setFeedInput(e.target.value)}
             className="feed-input"
@@ -52,29 +126,194 @@ export function Sidebar({
       </div>
 
       <div className="feeds-list">
-        {feeds.length === 0 ? (
+        <div
+          className={`feed-item ${showsAll ? 'selected' : ''}`}
+          onClick={onShowAllArticles}
+        >
+          <div className="feed-item-title">
+            📰 All Articles
+            {totalUnread > 0 && <span className="unread-badge">{totalUnread}</span>}
+          </div>
+          <div className="feed-item-actions" onClick={(e) => e.stopPropagation()}>
+            <button className="feed-action-btn" title="Refresh all feeds" onClick={onRefreshAll}>
+              ⟳ Refresh all
+            </button>
+            <button className="feed-action-btn" title="Mark all articles as read" onClick={onMarkAllRead}>
+              ✓ Mark all read
+            </button>
+          </div>
+        </div>
+        <div className={`feed-item ${showStarred ? 'selected' : ''}`} onClick={onShowStarred}>
+          <div className="feed-item-title">
+            ⭐ Starred
+            {starredCount > 0 && <span className="unread-badge">{starredCount}</span>}
+          </div>
+        </div>
+        {savedSearches.map((search) => (
+          <div
+            key={search.id}
+            className={`feed-item ${selectedSavedSearch === search.id ? 'selected' : ''}`}
+            onClick={() => onSelectSavedSearch(search.id)}
+          >
+            <div className="feed-item-title">
+              🔎 {search.name}
+              {(savedSearchUnread[search.id] ?? 0) > 0 && (
+                <span className="unread-badge">{savedSearchUnread[search.id]}</span>
+              )}
+            </div>
+            <div className="feed-item-actions" onClick={(e) => e.stopPropagation()}>
+              <button
+                className="feed-action-btn"
+                title="Delete saved search"
+                onClick={() => onDeleteSavedSearch(search.id)}
+              >
+                ✕ Delete
+              </button>
+            </div>
+          </div>
+        ))}
+        {feeds.length === 0 && folders.length === 0 ? (
           <div className="empty-state">
             <p className="empty-state-text">No feeds yet. Add one above!</p>
           </div>
         ) : (
-          feeds.map((feed, index) => (
-            <div
-              key={index}
-              className="feed-item"
-              onClick={() => onRefreshFeed(feed.url)}
-            >
-              <div className="feed-item-title">{feed.title}</div>
-              <div className="feed-item-url">{feed.url}</div>
-            </div>
-          ))
+          <>
+            {topLevel.map((folder) => (
+              <div key={folder.id} className="folder">
+                {renderFolder(folder)}
+                {!folder.collapsed && (
+                  <div className="folder-children">
+                    {subfolders(folder.id).map((subfolder) => (
+                      <div key={subfolder.id} className="folder">
+                        {renderFolder(subfolder)}
+                        {!subfolder.collapsed && (
+                          <div className="folder-children">
+                            {feeds.filter((f) => f.folder_id === subfolder.id).map(renderFeed)}
+                          </div>
+                        )}
+                      </div>
+                    ))}
+                    {feeds.filter((f) => f.folder_id === folder.id).map(renderFeed)}
+                  </div>
+                )}
+              </div>
+            ))}
+            {unfiled.map(renderFeed)}
+          </>
         )}
+        <button className="btn btn-secondary new-folder-btn" onClick={() => onCreateFolder(newFolderParent)}>
+          📁 {newFolderParent === null ? 'New Folder' : `New Folder in ${selected?.name}`}
+        </button>
       </div>
 
       <div className="sidebar-footer">
+        <button className="btn btn-secondary" onClick={onOpenFeedHealth}>
+          🩺 Feed Health{failing > 0 && ` (${failing} failing)`}
+        </button>
         <button className="btn btn-secondary" onClick={onOpenSettings}>
           ⚙️ Settings
         </button>
       </div>
     </div>
   );
+
+  function renderFolder(folder: Folder) {
+    const unread = folderUnread(folder.id);
+    return (
+      <div
+        className={`feed-item folder-item ${selectedFolder === folder.id ? 'selected' : ''}`}
+        onClick={() => onSelectFolder(folder.id)}
+      >
+        <div className="feed-item-title">
+          <button
+            className="feed-action-btn"
+            title={folder.collapsed ? 'Expand' : 'Collapse'}
+            onClick={(e) => {
+              e.stopPropagation();
+              onToggleFolder(folder);
+            }}
+          >
+            {folder.collapsed ? '▸' : '▾'}
+          </button>
+          📁 {folder.name}
+          {unread > 0 && <span className="unread-badge">{unread}</span>}
+        </div>
+        <div className="feed-item-actions" onClick={(e) => e.stopPropagation()}>
+          <button className="feed-action-btn" title="Rename folder" onClick={() => onRenameFolder(folder)}>
+            ✎
+          </button>
+          <button className="feed-action-btn" title="Delete folder" onClick={() => onDeleteFolder(folder.id)}>
+            ✕
+          </button>
+        </div>
+      </div>
+    );
+  }
+
+  function renderFeed(feed: Feed) {
+    return (
+      <div
+        key={feed.url}
+        className={`feed-item ${selectedFeed === feed.url ? 'selected' : ''}`}
+        title={statusMessage(feedStatus[feed.url])}
+        onClick={() => onSelectFeed(feed.url)}
+      >
+        <div className="feed-item-title">
+          {feedStatus[feed.url]?.status === 'pending' && '⏳ '}
+          {feedStatus[feed.url]?.status === 'error' && '⚠ '}
+          {feed.dead && feedStatus[feed.url]?.status !== 'pending' && '⛔ '}
+          {feed.title}
+          {(unreadCounts[feed.url] ?? 0) > 0 && <span className="unread-badge">{unreadCounts[feed.url]}</span>}
+        </div>
+        <div className="feed-item-url">{feed.url}</div>
+        <div className="feed-item-actions" onClick={(e) => e.stopPropagation()}>
+          <button className="feed-action-btn" title="Move up" onClick={() => onMoveFeed(feed.url, -1)}>
+            ↑
+          </button>
+          <button className="feed-action-btn" title="Move down" onClick={() => onMoveFeed(feed.url, 1)}>
+            ↓
+          </button>
+          <button className="feed-action-btn" title="Mark feed as read" onClick={() => onMarkFeedRead(feed.url)}>
+            ✓
+          </button>
+          <select
+            className="feed-folder-select"
+            title="Move to folder"
+            value={feed.folder_id ?? ''}
+            onChange={(e) => onMoveFeedToFolder(feed.url, e.target.value === '' ? null : Number(e.target.value))}
+          >
+            <option value="">No folder</option>
+            {folders.map((folder) => (
+              <option key={folder.id} value={folder.id}>
+                {folder.parent_id === null
+                  ? folder.name
+                  : `${folders.find((f) => f.id === folder.parent_id)?.name} / ${folder.name}`}
+              </option>
+            ))}
+          </select>
+          <select
+            className="feed-folder-select"
+            title="Refresh interval"
+            value={feed.refresh_interval_minutes ?? ''}
+            onChange={(e) => onSetFeedRefreshInterval(feed.url, e.target.value === '' ? null : Number(e.target.value))}
+          >
+            <option value="">Same as other feeds</option>
+            {REFRESH_INTERVALS.map(([minutes, label]) => (
+              <option key={minutes} value={minutes}>
+                {label}
+              </option>
+            ))}
+          </select>
+          <button className="feed-action-btn" title="Unsubscribe" onClick={() => onRemoveFeed(feed.url)}>
+            ✕
+          </button>
+        </div>
+      </div>
+    );
+  }
+}
+
+/** Tooltip explaining why a feed's last refresh failed */
+function statusMessage(status: FeedStatus | undefined): string | undefined {
+  return status?.status === 'error' ? describeError(status.message) : undefined;
 }
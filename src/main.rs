@@ -1,38 +1,108 @@
-use iced::{Element, Task, Theme};
+use chrono::Utc;
+use iced::{Element, Subscription, Task, Theme};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 
 mod db;
+mod error;
 mod feed_engine;
 mod models;
+mod opml;
+mod scheduler;
 mod ui;
 mod utils;
 
 use db::ArticleDatabase;
-use models::{AppSettings, Article, Feed, FeedData};
-use ui::{content::ContentMessage, settings::SettingsMessage, sidebar::SidebarMessage};
+use error::FeedError;
+use feed_engine::{Discovery, FeedEngine, FeedResponse, FetchOutcome};
+use models::{
+    AppSettings, Article, CacheValidators, Feed, FeedCandidate, FeedStatus, Folder, HttpSettings, ImportPlan,
+    SavedSearch, StorageStats,
+};
+use scheduler::RefreshScheduler;
+use utils::html_to_text;
+use ui::{
+    article_detail::ArticleDetailMessage,
+    content::{ContentMessage, Search},
+    feed_chooser::FeedChooserMessage, feed_health::FeedHealthMessage, settings::SettingsMessage,
+    sidebar::{Selection, SidebarMessage},
+};
 
 fn main() -> iced::Result {
     iced::application("Czytaj - RSS Reader", App::update, App::view)
         .theme(App::theme)
+        .subscription(App::subscription)
         .run_with(App::new)
 }
 
 struct App {
     feeds: Vec<Feed>,
+    folders: Vec<Folder>,
+    /// Searches shown as smart feeds in the sidebar
+    saved_searches: Vec<SavedSearch>,
     articles: Vec<Article>,
+    /// What the article list shows
+    selection: Selection,
     feed_input: String,
-    loading: bool,
+    /// Name typed for a new or renamed folder
+    folder_input: String,
+    /// Unread articles per feed URL
+    unread_counts: HashMap<String, usize>,
+    /// Unread articles matching each saved search, keyed by ID
+    saved_search_unread: HashMap<i64, usize>,
+    /// `(feed_url, entry_id)` keys of the articles picked for bulk actions
+    selected_articles: HashSet<(String, String)>,
+    /// `(feed_url, entry_id)` keys of read and of starred articles, kept here
+    /// so drawing never waits on the database while it is being cleaned up
+    read_articles: HashSet<(String, String)>,
+    starred_articles: HashSet<(String, String)>,
+    search: Search,
+    /// Result of the latest refresh of each feed, keyed by URL
+    feed_status: HashMap<String, FeedStatus>,
+    /// Feeds added in this session whose first fetch hasn't succeeded yet
+    unconfirmed_feeds: HashSet<String>,
+    /// Bounds how many feeds are fetched at the same time
+    fetch_limiter: Arc<Semaphore>,
+    feed_engine: FeedEngine,
+    scheduler: RefreshScheduler,
     settings: AppSettings,
     show_settings: bool,
+    /// Database size and contents, read when settings are opened
+    storage: Option<StorageStats>,
+    show_feed_health: bool,
+    /// Feeds found on a web page the user tried to add, keyed by the page's
+    /// URL, until they pick one
+    feed_chooser: Option<(String, Vec<FeedCandidate>)>,
+    /// File the OPML import and export in settings read and write
+    opml_path: String,
+    /// Dry run of an OPML import, waiting for the user to confirm it
+    import_plan: Option<ImportPlan>,
+    /// Outcome of the last OPML import or export
+    opml_status: Option<String>,
     db: ArticleDatabase,
     current_article: Option<Article>,
+    /// Body of the open article as plain text, converted once when it was
+    /// opened rather than on every redraw
+    current_article_body: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 enum Message {
     Sidebar(SidebarMessage),
     Content(ContentMessage),
+    ArticleDetail(ArticleDetailMessage),
     Settings(SettingsMessage),
-    FeedFetched(String, Result<FeedData, String>),
+    FeedHealth(FeedHealthMessage),
+    FeedChooser(FeedChooserMessage),
+    FeedDiscovered(String, Result<Discovery, FeedError>),
+    FeedFetched(String, Result<FeedResponse, FeedError>),
+    SchedulerTick(Instant),
+    /// Time to purge old articles and tidy up the database
+    CleanupTick,
+    /// Old articles were purged, this many of them
+    CleanedUp(Result<usize, FeedError>),
     ArticleClicked(Article),
     BackToList,
 }
@@ -44,20 +114,73 @@ impl App {
             // In case of failure, try again (this will likely work or panic)
             ArticleDatabase::new().expect("Could not create database")
         });
+
+        (Self::with_db(db, AppSettings::load()), Task::none())
+    }
+
+    /// Build the app state from what is stored in `db`
+    fn with_db(db: ArticleDatabase, settings: AppSettings) -> Self {
+        let feeds = db.list_feeds().unwrap_or_else(|e| {
+            eprintln!("Failed to load feeds: {}", e);
+            vec![]
+        });
+
+        let folders = db.list_folders().unwrap_or_else(|e| {
+            eprintln!("Failed to load folders: {}", e);
+            vec![]
+        });
+
+        let saved_searches = db.list_saved_searches().unwrap_or_else(|e| {
+            eprintln!("Failed to load saved searches: {}", e);
+            vec![]
+        });
+
+        let articles = db.get_timeline().unwrap_or_else(|e| {
+            eprintln!("Failed to load articles: {}", e);
+            vec![]
+        });
+
+        let engine = FeedEngine::new(&settings.http).unwrap_or_else(|e| {
+            eprintln!("{}; falling back to default HTTP settings", e);
+            FeedEngine::new(&HttpSettings::default()).expect("Could not create HTTP client")
+        });
         
-        (
-            Self {
-                feeds: vec![],
-                articles: vec![],
-                feed_input: String::new(),
-                loading: false,
-                settings: AppSettings::default(),
-                show_settings: false,
-                db,
-                current_article: None,
-            },
-            Task::none(),
-        )
+        let mut app = Self {
+            unread_counts: HashMap::new(),
+            saved_search_unread: HashMap::new(),
+            feeds,
+            folders,
+            saved_searches,
+            articles,
+            selection: Selection::AllArticles,
+            feed_input: String::new(),
+            folder_input: String::new(),
+            selected_articles: HashSet::new(),
+            read_articles: HashSet::new(),
+            starred_articles: HashSet::new(),
+            search: Search::default(),
+            feed_status: HashMap::new(),
+            unconfirmed_feeds: HashSet::new(),
+            fetch_limiter: Arc::new(Semaphore::new(feed_engine::MAX_CONCURRENT_FETCHES)),
+            feed_engine: engine,
+            scheduler: RefreshScheduler::new(),
+            settings,
+            show_settings: false,
+            storage: None,
+            show_feed_health: false,
+            feed_chooser: None,
+            opml_path: dirs::home_dir()
+                .map(|home| home.join("czytaj-subscriptions.opml").display().to_string())
+                .unwrap_or_default(),
+            import_plan: None,
+            opml_status: None,
+            db,
+            current_article: None,
+            current_article_body: None,
+        };
+        app.reload_read_state();
+        app.reload_starred();
+        app
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
@@ -72,105 +195,949 @@ impl App {
                         return Task::none();
                     }
 
-                    let url = self.feed_input.clone();
-                    self.feeds.push(Feed::new(url.clone(), "Loading...".to_string()));
+                    let url = self.feed_input.trim().to_string();
                     self.feed_input.clear();
-                    self.loading = true;
-
-                    Task::perform(
-                        async move {
-                            let result = feed_engine::fetch_feed(url.clone()).await;
-                            (url, result)
-                        },
-                        |(url, result)| Message::FeedFetched(url, result),
-                    )
-                }
-                SidebarMessage::RefreshFeed(url) => {
-                    self.loading = true;
-                    Task::perform(
-                        async move {
-                            let result = feed_engine::fetch_feed(url.clone()).await;
-                            (url, result)
-                        },
-                        |(url, result)| Message::FeedFetched(url, result),
-                    )
+                    self.add_feed(url)
+                }
+                SidebarMessage::ShowAllArticles => {
+                    self.select(Selection::AllArticles);
+
+                    self.refresh_all()
+                }
+                SidebarMessage::ShowStarred => {
+                    self.select(Selection::Starred);
+                    Task::none()
+                }
+                SidebarMessage::RefreshAll => self.refresh_all(),
+                SidebarMessage::SelectFeed(url) => {
+                    // Show what we have stored right away, then refresh
+                    self.select(Selection::Feed(url.clone()));
+
+                    self.fetch_feed(url)
+                }
+                SidebarMessage::RemoveFeed(url) => {
+                    self.remove_feed(&url);
+                    Task::none()
+                }
+                SidebarMessage::MoveFeedUp(url) => {
+                    // Feeds swap places with their neighbour in the same folder
+                    if let Some(index) = self.feeds.iter().position(|f| f.url == url) {
+                        let folder_id = self.feeds[index].folder_id;
+                        if let Some(other) = self.feeds[..index].iter().rposition(|f| f.folder_id == folder_id) {
+                            self.feeds.swap(index, other);
+                            self.save_feed_order();
+                        }
+                    }
+                    Task::none()
+                }
+                SidebarMessage::MoveFeedDown(url) => {
+                    if let Some(index) = self.feeds.iter().position(|f| f.url == url) {
+                        let folder_id = self.feeds[index].folder_id;
+                        if let Some(offset) = self.feeds[index + 1..].iter().position(|f| f.folder_id == folder_id) {
+                            self.feeds.swap(index, index + 1 + offset);
+                            self.save_feed_order();
+                        }
+                    }
+                    Task::none()
+                }
+                SidebarMessage::SelectFolder(id) => {
+                    self.select(Selection::Folder(id));
+
+                    let urls: Vec<String> = self
+                        .feeds
+                        .iter()
+                        .filter(|f| !f.dead && self.shows_feed(&f.url))
+                        .map(|f| f.url.clone())
+                        .collect();
+                    Task::batch(urls.into_iter().map(|url| self.fetch_feed(url)))
+                }
+                SidebarMessage::ToggleFolder(id) => {
+                    if let Some(folder) = self.folders.iter_mut().find(|f| f.id == id) {
+                        folder.collapsed = !folder.collapsed;
+                        if let Err(e) = self.db.set_folder_collapsed(id, folder.collapsed) {
+                            eprintln!("Failed to save folder: {}", e);
+                        }
+                    }
+                    Task::none()
+                }
+                SidebarMessage::DeleteFolder(id) => {
+                    if let Err(e) = self.db.delete_folder(id) {
+                        eprintln!("Failed to delete folder: {}", e);
+                    }
+                    if self.selection == Selection::Folder(id) {
+                        self.selection = Selection::AllArticles;
+                        self.reload_articles();
+                    }
+                    self.reload_folders();
+                    Task::none()
+                }
+                SidebarMessage::FolderInputChanged(value) => {
+                    self.folder_input = value;
+                    Task::none()
+                }
+                SidebarMessage::CreateFolder(parent_id) => {
+                    let name = self.folder_input.trim();
+                    if name.is_empty() {
+                        return Task::none();
+                    }
+                    match self.db.create_folder(name, parent_id) {
+                        Ok(_) => self.folder_input.clear(),
+                        Err(e) => eprintln!("Failed to create folder: {}", e),
+                    }
+                    self.reload_folders();
+                    Task::none()
+                }
+                SidebarMessage::RenameFolder(id) => {
+                    let name = self.folder_input.trim();
+                    if name.is_empty() {
+                        return Task::none();
+                    }
+                    match self.db.rename_folder(id, name) {
+                        Ok(()) => self.folder_input.clear(),
+                        Err(e) => eprintln!("Failed to rename folder: {}", e),
+                    }
+                    self.reload_folders();
+                    Task::none()
+                }
+                SidebarMessage::MoveFeedToFolder(url, folder_id) => {
+                    if let Err(e) = self.db.set_feed_folder(&url, folder_id) {
+                        eprintln!("Failed to move feed: {}", e);
+                    } else if let Some(feed) = self.feeds.iter_mut().find(|f| f.url == url) {
+                        feed.folder_id = folder_id;
+                    }
+                    Task::none()
+                }
+                SidebarMessage::SetFeedRefreshInterval(url, minutes) => {
+                    if let Err(e) = self.db.set_feed_refresh_interval(&url, minutes) {
+                        eprintln!("Failed to save refresh interval: {}", e);
+                    } else if let Some(feed) = self.feeds.iter_mut().find(|f| f.url == url) {
+                        feed.refresh_interval_minutes = minutes;
+                    }
+                    Task::none()
+                }
+                SidebarMessage::SelectSavedSearch(id) => {
+                    self.select(Selection::SavedSearch(id));
+                    Task::none()
+                }
+                SidebarMessage::DeleteSavedSearch(id) => {
+                    if let Err(e) = self.db.delete_saved_search(id) {
+                        eprintln!("Failed to delete saved search: {}", e);
+                    }
+                    self.saved_searches.retain(|s| s.id != id);
+                    if self.selection == Selection::SavedSearch(id) {
+                        self.select(Selection::AllArticles);
+                    }
+                    Task::none()
+                }
+                SidebarMessage::OpenFeedHealth => {
+                    self.show_feed_health = true;
+                    Task::none()
                 }
                 SidebarMessage::OpenSettings => {
                     self.show_settings = true;
+                    self.reload_storage_stats();
                     Task::none()
                 }
             },
             Message::Content(content_msg) => match content_msg {
                 ContentMessage::ArticleClicked(article) => {
                     // Forward to main message handler
-                    self.update(Message::ArticleClicked(article))
+                    self.update(Message::ArticleClicked(*article))
+                }
+                ContentMessage::MarkAllRead => {
+                    let marked = match &self.selection {
+                        Selection::AllArticles => self.db.mark_all_read(),
+                        Selection::Feed(url) => self.db.mark_feed_read(url),
+                        Selection::Folder(_) => self
+                            .feeds
+                            .iter()
+                            .filter(|f| self.shows_feed(&f.url))
+                            .try_fold(0, |marked, f| Ok(marked + self.db.mark_feed_read(&f.url)?)),
+                        Selection::Starred | Selection::SavedSearch(_) => {
+                            let keys: Vec<_> = self.articles.iter().map(|a| (a.feed_url.clone(), a.id.clone())).collect();
+                            self.db.set_articles_read(&keys, true)
+                        }
+                    };
+                    if let Err(e) = marked {
+                        eprintln!("Failed to mark articles as read: {}", e);
+                    }
+                    self.reload_articles();
+                    Task::none()
+                }
+                ContentMessage::MarkOlderRead(days) => {
+                    if let Err(e) = self.db.mark_read_older_than(days) {
+                        eprintln!("Failed to mark articles as read: {}", e);
+                    }
+                    self.reload_articles();
+                    Task::none()
+                }
+                ContentMessage::ToggleRead(feed_url, entry_id) => {
+                    self.toggle_read(feed_url, entry_id);
+                    Task::none()
+                }
+                ContentMessage::ToggleStar(article) => {
+                    self.toggle_star(&article);
+                    Task::none()
+                }
+                ContentMessage::ToggleSelected(feed_url, entry_id) => {
+                    let key = (feed_url, entry_id);
+                    if !self.selected_articles.remove(&key) {
+                        self.selected_articles.insert(key);
+                    }
+                    Task::none()
+                }
+                ContentMessage::MarkSelected(read) => {
+                    let keys: Vec<_> = self.selected_articles.drain().collect();
+                    if let Err(e) = self.db.set_articles_read(&keys, read) {
+                        eprintln!("Failed to update read state: {}", e);
+                    }
+                    self.reload_read_state();
+                    Task::none()
+                }
+                ContentMessage::ClearSelection => {
+                    self.selected_articles.clear();
+                    Task::none()
+                }
+                ContentMessage::SearchChanged(query) => {
+                    self.search.query = query;
+                    Task::none()
+                }
+                ContentMessage::Search => {
+                    self.run_search();
+                    Task::none()
+                }
+                ContentMessage::ToggleUnreadOnly(unread_only) => {
+                    self.search.unread_only = unread_only;
+                    if self.search.results.is_some() {
+                        self.run_search();
+                    }
+                    Task::none()
+                }
+                ContentMessage::MaxAgeChanged(max_age_days) => {
+                    self.search.max_age_days = max_age_days;
+                    if self.search.results.is_some() {
+                        self.run_search();
+                    }
+                    Task::none()
+                }
+                ContentMessage::ClearSearch => {
+                    self.search = Search::default();
+                    Task::none()
+                }
+                ContentMessage::SaveNameChanged(name) => {
+                    self.search.save_name = name;
+                    Task::none()
+                }
+                ContentMessage::SaveSearch => {
+                    self.save_search();
+                    Task::none()
+                }
+            },
+            Message::ArticleDetail(detail_msg) => match detail_msg {
+                ArticleDetailMessage::BackToList => self.update(Message::BackToList),
+                ArticleDetailMessage::ToggleRead => {
+                    if let Some(article) = &self.current_article {
+                        self.toggle_read(article.feed_url.clone(), article.id.clone());
+                    }
+                    Task::none()
+                }
+                ArticleDetailMessage::ToggleStar => {
+                    if let Some(article) = self.current_article.clone() {
+                        self.toggle_star(&article);
+                    }
+                    Task::none()
                 }
             },
             Message::Settings(settings_msg) => match settings_msg {
                 SettingsMessage::ThemeSelected(theme) => {
                     self.settings.theme = theme;
+                    self.save_settings();
                     Task::none()
                 }
                 SettingsMessage::ToggleImages => {
                     self.settings.show_images = !self.settings.show_images;
+                    self.save_settings();
                     Task::none()
                 }
                 SettingsMessage::ToggleExcerpts => {
                     self.settings.show_excerpts = !self.settings.show_excerpts;
+                    self.save_settings();
+                    Task::none()
+                }
+                SettingsMessage::RefreshIntervalSelected(minutes) => {
+                    self.settings.refresh_interval_minutes = minutes;
+                    self.save_settings();
+                    Task::none()
+                }
+                SettingsMessage::TogglePauseRefresh => {
+                    self.settings.refresh_paused = !self.settings.refresh_paused;
+                    self.save_settings();
+                    Task::none()
+                }
+                SettingsMessage::RetentionAgeSelected(days) => {
+                    self.settings.retention.max_age_days = days;
+                    self.save_settings();
+                    Task::none()
+                }
+                SettingsMessage::RetentionCountSelected(count) => {
+                    self.settings.retention.max_articles_per_feed = count;
+                    self.save_settings();
+                    Task::none()
+                }
+                SettingsMessage::CleanUpNow => self.clean_up(true),
+                SettingsMessage::OpmlPathChanged(path) => {
+                    self.opml_path = path;
+                    Task::none()
+                }
+                SettingsMessage::PreviewImport => {
+                    let plan = std::fs::read_to_string(&self.opml_path)
+                        .map_err(|e| format!("Failed to read {}: {}", self.opml_path, e))
+                        .and_then(|xml| opml::plan_import(&xml, &self.feeds).map_err(|e| e.to_string()));
+                    match plan {
+                        Ok(plan) => {
+                            self.import_plan = Some(plan);
+                            self.opml_status = None;
+                        }
+                        Err(e) => {
+                            self.import_plan = None;
+                            self.opml_status = Some(e);
+                        }
+                    }
+                    Task::none()
+                }
+                SettingsMessage::ConfirmImport => {
+                    let Some(plan) = self.import_plan.take() else {
+                        return Task::none();
+                    };
+                    match opml::apply_import(&self.db, &plan).and_then(|added| Ok((added, self.db.list_feeds()?))) {
+                        Ok((added, feeds)) => {
+                            self.feeds = feeds;
+                            self.opml_status = Some(format!("Imported {} feeds", added));
+                        }
+                        Err(e) => self.opml_status = Some(format!("Failed to import feeds: {}", e)),
+                    }
+                    self.reload_folders();
+                    // Fetch the new feeds right away rather than waiting for the scheduler
+                    Task::batch(plan.new_feeds.into_iter().map(|f| self.fetch_feed(f.url)))
+                }
+                SettingsMessage::CancelImport => {
+                    self.import_plan = None;
+                    Task::none()
+                }
+                SettingsMessage::ExportOpml => {
+                    self.opml_status = Some(match std::fs::write(&self.opml_path, opml::export_opml(&self.feeds, &self.folders)) {
+                        Ok(()) => format!("Exported {} feeds to {}", self.feeds.len(), self.opml_path),
+                        Err(e) => format!("Failed to write {}: {}", self.opml_path, e),
+                    });
                     Task::none()
                 }
                 SettingsMessage::CloseSettings => {
                     self.show_settings = false;
+                    self.import_plan = None;
+                    self.opml_status = None;
+                    Task::none()
+                }
+            },
+            Message::FeedHealth(health_msg) => match health_msg {
+                FeedHealthMessage::RemoveFeed(url) => {
+                    self.remove_feed(&url);
+                    Task::none()
+                }
+                FeedHealthMessage::Close => {
+                    self.show_feed_health = false;
+                    Task::none()
+                }
+            },
+            Message::FeedChooser(chooser_msg) => {
+                self.feed_chooser = None;
+                match chooser_msg {
+                    FeedChooserMessage::Choose(url) => self.add_feed(url),
+                    FeedChooserMessage::Cancel => Task::none(),
+                }
+            }
+            Message::FeedDiscovered(url, result) => match result {
+                Ok(Discovery::Feed(response)) => self.update(Message::FeedFetched(url, Ok(response))),
+                Ok(Discovery::Candidates(candidates)) => {
+                    // The page itself isn't a feed; drop its placeholder until one is picked
+                    self.feed_status.remove(&url);
+                    self.unconfirmed_feeds.remove(&url);
+                    self.feeds.retain(|f| f.url != url);
+                    if self.selection.feed() == Some(url.as_str()) {
+                        self.selection = Selection::AllArticles;
+                        self.reload_articles();
+                    }
+                    self.feed_chooser = Some((url, candidates));
                     Task::none()
                 }
+                Err(e) => self.update(Message::FeedFetched(url, Err(e))),
             },
             Message::FeedFetched(url, result) => {
-                self.loading = false;
-                match result {
-                    Ok(feed_data) => {
+                // Removed while it was being fetched; saving the result would
+                // subscribe to it again
+                if !self.feeds.iter().any(|f| f.url == url) {
+                    return Task::none();
+                }
+                let url = match &result {
+                    Ok(FeedResponse { moved_to: Some(new_url), .. }) => self.move_feed(url, new_url),
+                    _ => url,
+                };
+                let status = match &result {
+                    Ok(response) => match response.outcome {
+                        FetchOutcome::Updated(..) => FeedStatus::Ok,
+                        FetchOutcome::NotModified(_) => FeedStatus::NotModified,
+                        FetchOutcome::Gone => FeedStatus::Gone,
+                    },
+                    Err(e) => FeedStatus::Error(e.clone()),
+                };
+                self.feed_status.insert(url.clone(), status);
+                let health_outcome = match &result {
+                    Ok(response) if matches!(response.outcome, FetchOutcome::Gone) => Err(FeedError::HttpStatus(410)),
+                    Ok(response) => Ok(response.latency),
+                    Err(e) => Err(e.clone()),
+                };
+
+                match result.map(|response| response.outcome) {
+                    Ok(FetchOutcome::Updated(feed_data, validators)) => {
+                        self.unconfirmed_feeds.remove(&url);
                         // Update feed title
                         if let Some(feed) = self.feeds.iter_mut().find(|f| f.url == url) {
-                            feed.title = feed_data.title;
+                            feed.title = feed_data.title.clone();
+                            if feed.dead {
+                                feed.dead = false;
+                                if let Err(e) = self.db.set_feed_dead(&url, false) {
+                                    eprintln!("Failed to update feed: {}", e);
+                                }
+                            }
+                        }
+                        // Persist the subscription now that we know the feed is valid
+                        if let Err(e) = self.db.add_feed(&url, &feed_data.title) {
+                            eprintln!("Failed to save feed: {}", e);
+                        }
+                        if let Err(e) = self.db.set_cache_validators(&url, &validators) {
+                            eprintln!("Failed to save cache validators: {}", e);
+                        }
+                        if let Err(e) = self.db.upsert_articles(&url, &feed_data.articles) {
+                            eprintln!("Failed to store articles: {}", e);
+                        }
+                        if self.shows_feed(&url) {
+                            self.reload_articles();
+                        } else {
+                            self.reload_read_state();
+                        }
+                    }
+                    Ok(FetchOutcome::NotModified(validators)) => {
+                        if let Err(e) = self.db.set_cache_validators(&url, &validators) {
+                            eprintln!("Failed to save cache validators: {}", e);
+                        }
+                    }
+                    Ok(FetchOutcome::Gone) => {
+                        eprintln!("Feed is gone: {}", url);
+                        if self.unconfirmed_feeds.remove(&url) {
+                            self.feeds.retain(|f| f.url != url);
+                        } else if let Some(feed) = self.feeds.iter_mut().find(|f| f.url == url) {
+                            feed.dead = true;
+                            if let Err(e) = self.db.set_feed_dead(&url, true) {
+                                eprintln!("Failed to update feed: {}", e);
+                            }
                         }
-                        self.articles = feed_data.articles;
                     }
                     Err(e) => {
                         eprintln!("Error fetching feed: {}", e);
-                        // Remove the feed if it failed to load
-                        self.feeds.retain(|f| f.url != url);
+                        // Subscribed feeds keep their error badge until the
+                        // next refresh; a newly added one that never loaded is dropped
+                        if self.unconfirmed_feeds.remove(&url) {
+                            self.feeds.retain(|f| f.url != url);
+                        }
                     }
                 }
+                self.record_health(&url, health_outcome);
                 Task::none()
             }
-            Message::ArticleClicked(article) => {
+            Message::SchedulerTick(now) => {
+                let interval = Duration::from_secs(self.settings.refresh_interval_minutes * 60);
+                let due = self.scheduler.due_feeds(&self.feeds, interval, now);
+                Task::batch(due.into_iter().map(|url| self.fetch_feed(url)))
+            }
+            Message::CleanupTick => self.clean_up(false),
+            Message::CleanedUp(result) => {
+                match result {
+                    Ok(0) => {}
+                    Ok(_) => self.reload_articles(),
+                    Err(e) => eprintln!("Failed to clean up the database: {}", e),
+                }
+                if self.show_settings {
+                    self.reload_storage_stats();
+                }
+                Task::none()
+            }
+            Message::ArticleClicked(mut article) => {
                 // Mark article as viewed in database
-                if let Err(e) = self.db.mark_as_viewed(&article.link, &article.title) {
+                if let Err(e) = self.db.mark_as_viewed(&article.feed_url, &article.id, &article.title) {
                     eprintln!("Failed to mark article as viewed: {}", e);
                 }
+                // Lists leave out the body; load it for reading
+                if article.content.is_none() {
+                    match self.db.get_article_content(&article.feed_url, &article.id) {
+                        Ok(content) => article.content = content,
+                        Err(e) => eprintln!("Failed to load article: {}", e),
+                    }
+                }
+                self.reload_read_state();
+                self.current_article_body = article.content.as_deref().map(html_to_text).filter(|body| !body.is_empty());
                 self.current_article = Some(article);
                 Task::none()
             }
             Message::BackToList => {
                 self.current_article = None;
+                self.current_article_body = None;
                 Task::none()
             }
         }
     }
 
+    /// Subscribe to the feed at `url`, or to one found on the web page there.
+    /// Already subscribed feeds are just refreshed.
+    fn add_feed(&mut self, url: String) -> Task<Message> {
+        self.selection = Selection::Feed(url.clone());
+        if self.feeds.iter().any(|f| f.url == url) {
+            self.reload_articles();
+            return self.fetch_feed(url);
+        }
+        self.feeds.push(Feed::new(url.clone(), "Loading...".to_string()));
+        self.unconfirmed_feeds.insert(url.clone());
+        self.reload_articles();
+
+        self.feed_status.insert(url.clone(), FeedStatus::Pending);
+        let engine = self.feed_engine.clone();
+        Task::perform(
+            async move {
+                let result = engine.discover(url.clone()).await;
+                (url, result)
+            },
+            |(url, result)| Message::FeedDiscovered(url, result),
+        )
+    }
+
+    fn fetch_feed(&mut self, url: String) -> Task<Message> {
+        // Don't queue a second fetch of a feed that is still in flight
+        if self.feed_status.get(&url) == Some(&FeedStatus::Pending) {
+            return Task::none();
+        }
+        self.feed_status.insert(url.clone(), FeedStatus::Pending);
+
+        let validators = self.db.get_cache_validators(&url).unwrap_or_else(|e| {
+            eprintln!("Failed to load cache validators: {}", e);
+            CacheValidators::default()
+        });
+        let limiter = Arc::clone(&self.fetch_limiter);
+        let engine = self.feed_engine.clone();
+        Task::perform(
+            async move {
+                let result = engine.fetch_feed_limited(limiter, url.clone(), validators).await;
+                (url, result)
+            },
+            |(url, result)| Message::FeedFetched(url, result),
+        )
+    }
+
+    fn remove_feed(&mut self, url: &str) {
+        if let Err(e) = self.db.remove_feed(url) {
+            eprintln!("Failed to remove feed: {}", e);
+        }
+        self.feeds.retain(|f| f.url != url);
+        // A fetch still in flight is ignored when it lands, so it can't clear these
+        self.feed_status.remove(url);
+        self.unconfirmed_feeds.remove(url);
+        if self.selection.feed() == Some(url) {
+            self.selection = Selection::AllArticles;
+        }
+        self.reload_articles();
+    }
+
+    /// Update a feed's health with the outcome of its latest fetch
+    fn record_health(&mut self, url: &str, outcome: Result<Duration, FeedError>) {
+        let now = Utc::now();
+        let health = self.db.update_feed_health(url, |health| match &outcome {
+            Ok(latency) => health.record_success(now, *latency),
+            Err(e) => health.record_failure(now, e),
+        });
+        match health {
+            Ok(Some(health)) => {
+                if let Some(feed) = self.feeds.iter_mut().find(|f| f.url == url) {
+                    feed.health = health;
+                }
+            }
+            // Not subscribed (yet), nothing to track
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to record feed health: {}", e),
+        }
+    }
+
+    /// Point a feed that permanently moved at its new URL, returning the URL
+    /// it is known by afterwards
+    fn move_feed(&mut self, old_url: String, new_url: &str) -> String {
+        if let Err(e) = self.db.rename_feed(&old_url, new_url) {
+            eprintln!("Failed to move feed to {}: {}", new_url, e);
+            return old_url;
+        }
+        if self.unconfirmed_feeds.remove(&old_url) {
+            self.unconfirmed_feeds.insert(new_url.to_string());
+        }
+        if self.feeds.iter().any(|f| f.url == new_url) {
+            self.feeds.retain(|f| f.url != old_url);
+        } else if let Some(feed) = self.feeds.iter_mut().find(|f| f.url == old_url) {
+            feed.url = new_url.to_string();
+        }
+        self.feed_status.remove(&old_url);
+        if self.selection.feed() == Some(old_url.as_str()) {
+            self.selection = Selection::Feed(new_url.to_string());
+        }
+        new_url.to_string()
+    }
+
+    /// Refresh every live feed concurrently; results arrive one `FeedFetched` at a time
+    fn refresh_all(&mut self) -> Task<Message> {
+        let urls: Vec<String> = self
+            .feeds
+            .iter()
+            .filter(|f| !f.dead)
+            .map(|f| f.url.clone())
+            .collect();
+        Task::batch(urls.into_iter().map(|url| self.fetch_feed(url)))
+    }
+
+    fn pending_fetches(&self) -> usize {
+        self.feed_status
+            .values()
+            .filter(|status| **status == FeedStatus::Pending)
+            .count()
+    }
+
+    /// Why the last refresh of the selected feed failed, if it did
+    fn selected_feed_error(&self) -> Option<&FeedError> {
+        let url = self.selection.feed()?;
+        match self.feed_status.get(url) {
+            Some(FeedStatus::Error(error)) => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Reload the article list for the current selection from the database,
+    /// so history older than the feeds' current window stays readable
+    fn reload_articles(&mut self) {
+        let articles = match &self.selection {
+            Selection::AllArticles => self.db.get_timeline(),
+            Selection::Feed(url) => self.db.get_articles(url),
+            Selection::Folder(id) => self.db.get_folder_timeline(*id),
+            Selection::Starred => self
+                .db
+                .list_starred()
+                .map(|starred| starred.into_iter().map(|s| s.article).collect()),
+            Selection::SavedSearch(id) => match self.saved_searches.iter().find(|s| s.id == *id) {
+                Some(search) => self.db.get_saved_search_articles(search),
+                None => Ok(vec![]),
+            },
+        };
+        match articles {
+            Ok(articles) => self.articles = articles,
+            Err(e) => eprintln!("Failed to load articles: {}", e),
+        }
+        // Only articles still listed stay selected
+        let listed: HashSet<(&str, &str)> = self
+            .articles
+            .iter()
+            .map(|a| (a.feed_url.as_str(), a.id.as_str()))
+            .collect();
+        self.selected_articles
+            .retain(|(feed_url, entry_id)| listed.contains(&(feed_url.as_str(), entry_id.as_str())));
+        self.reload_read_state();
+    }
+
+    /// Show another feed, folder or view, searching it if a search is shown
+    fn select(&mut self, selection: Selection) {
+        self.selection = selection;
+        self.reload_articles();
+        if self.search.results.is_some() {
+            self.run_search();
+        }
+    }
+
+    /// Search stored articles for the query in the content header, within the
+    /// selected feed or folder if there is one
+    fn run_search(&mut self) {
+        if self.search.query.trim().is_empty() {
+            self.search.results = None;
+            return;
+        }
+        let folder_id = match self.selection {
+            Selection::Folder(id) => Some(id),
+            _ => None,
+        };
+        let results = self.db.search_articles(
+            &self.search.query,
+            self.selection.feed(),
+            folder_id,
+            self.search.unread_only,
+            self.search.max_age_days,
+        );
+        match results {
+            Ok(results) => self.search.results = Some(results),
+            Err(e) => eprintln!("Failed to search articles: {}", e),
+        }
+    }
+
+    /// Save the search in the content header as a smart feed, scoped to the
+    /// selected feed or folder, and show it
+    fn save_search(&mut self) {
+        let name = self.search.save_name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let mut search = SavedSearch {
+            id: 0,
+            name: name.to_string(),
+            query: self.search.query.trim().to_string(),
+            feed_url: self.selection.feed().map(str::to_string),
+            folder_id: match self.selection {
+                Selection::Folder(id) => Some(id),
+                _ => None,
+            },
+            unread_only: self.search.unread_only,
+            max_age_days: self.search.max_age_days,
+        };
+        match self.db.create_saved_search(&search) {
+            Ok(id) => search.id = id,
+            Err(e) => {
+                eprintln!("Failed to save search: {}", e);
+                return;
+            }
+        }
+        let id = search.id;
+        self.saved_searches.push(search);
+        self.saved_searches.sort_by_key(|s| s.name.to_lowercase());
+        self.search = Search::default();
+        self.select(Selection::SavedSearch(id));
+    }
+
+    /// Star an article, saving a snapshot of it, or unstar it
+    fn toggle_star(&mut self, article: &Article) {
+        let result = if self.db.is_starred(&article.feed_url, &article.id).unwrap_or(false) {
+            self.db.unstar_article(&article.feed_url, &article.id)
+        } else {
+            let feed_title = self
+                .feeds
+                .iter()
+                .find(|f| f.url == article.feed_url)
+                .map_or(article.feed_url.as_str(), |f| f.title.as_str());
+            self.db.star_article(article, feed_title)
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to update starred articles: {}", e);
+        }
+        self.reload_starred();
+        if self.selection == Selection::Starred {
+            self.reload_articles();
+        }
+    }
+
+    /// Flip an article between read and unread
+    fn toggle_read(&mut self, feed_url: String, entry_id: String) {
+        let result = if self.db.is_viewed(&feed_url, &entry_id).unwrap_or(false) {
+            self.db.mark_as_unread(&feed_url, &entry_id)
+        } else {
+            self.db.set_articles_read(&[(feed_url, entry_id)], true).map(|_| ())
+        };
+        if let Err(e) = result {
+            eprintln!("Failed to update read state: {}", e);
+        }
+        self.reload_read_state();
+    }
+
+    /// Reload which articles are read, and how many are unread per feed and
+    /// saved search
+    fn reload_read_state(&mut self) {
+        match self.db.get_viewed_articles() {
+            Ok(read) => self.read_articles = read.into_iter().collect(),
+            Err(e) => eprintln!("Failed to load read articles: {}", e),
+        }
+        match self.db.unread_counts() {
+            Ok(counts) => self.unread_counts = counts,
+            Err(e) => eprintln!("Failed to count unread articles: {}", e),
+        }
+        match self.db.saved_search_unread_counts() {
+            Ok(counts) => self.saved_search_unread = counts,
+            Err(e) => eprintln!("Failed to count unread articles: {}", e),
+        }
+    }
+
+    /// Purge articles past the retention limits and tidy up the database on a
+    /// blocking worker, rewriting the file only if `force_compact` is set or
+    /// much of it is free
+    fn clean_up(&mut self, force_compact: bool) -> Task<Message> {
+        let db = self.db.clone();
+        let retention = self.settings.retention;
+        Task::perform(
+            async move {
+                match tokio::task::spawn_blocking(move || db.clean_up(&retention, force_compact)).await {
+                    Ok(result) => result.map_err(FeedError::from),
+                    Err(e) => Err(FeedError::Database(e.to_string())),
+                }
+            },
+            Message::CleanedUp,
+        )
+    }
+
+    fn reload_starred(&mut self) {
+        match self.db.list_starred() {
+            Ok(starred) => {
+                self.starred_articles = starred
+                    .into_iter()
+                    .map(|s| (s.article.feed_url, s.article.id))
+                    .collect()
+            }
+            Err(e) => eprintln!("Failed to load starred articles: {}", e),
+        }
+    }
+
+    fn reload_storage_stats(&mut self) {
+        match self.db.storage_stats() {
+            Ok(stats) => self.storage = Some(stats),
+            Err(e) => eprintln!("Failed to read database size: {}", e),
+        }
+    }
+
+    fn reload_folders(&mut self) {
+        match self.db.list_folders() {
+            Ok(folders) => self.folders = folders,
+            Err(e) => eprintln!("Failed to load folders: {}", e),
+        }
+        // Feeds follow their folder when it is deleted...
+        match self.db.list_feeds() {
+            Ok(feeds) => {
+                for feed in &mut self.feeds {
+                    if let Some(stored) = feeds.iter().find(|f| f.url == feed.url) {
+                        feed.folder_id = stored.folder_id;
+                    }
+                }
+            }
+            Err(e) => eprintln!("Failed to load feeds: {}", e),
+        }
+        // ...and so do saved searches
+        match self.db.list_saved_searches() {
+            Ok(searches) => self.saved_searches = searches,
+            Err(e) => eprintln!("Failed to load saved searches: {}", e),
+        }
+    }
+
+    /// Whether articles from the feed at `url` belong in the current article list
+    fn shows_feed(&self, url: &str) -> bool {
+        match &self.selection {
+            Selection::AllArticles => true,
+            Selection::Feed(selected) => selected == url,
+            Selection::Starred => false,
+            Selection::Folder(id) => self.feed_in_folder(url, *id),
+            Selection::SavedSearch(id) => match self.saved_searches.iter().find(|s| s.id == *id) {
+                Some(search) => {
+                    search.feed_url.as_deref().is_none_or(|selected| selected == url)
+                        && search.folder_id.is_none_or(|folder_id| self.feed_in_folder(url, folder_id))
+                }
+                None => false,
+            },
+        }
+    }
+
+    /// Whether the feed at `url` is filed in a folder or its subfolders
+    fn feed_in_folder(&self, url: &str, folder_id: i64) -> bool {
+        let folder_ids = ui::sidebar::folder_and_subfolders(&self.folders, folder_id);
+        self.feeds
+            .iter()
+            .any(|f| f.url == url && f.folder_id.is_some_and(|id| folder_ids.contains(&id)))
+    }
+
+    fn save_feed_order(&self) {
+        let urls: Vec<String> = self.feeds.iter().map(|f| f.url.clone()).collect();
+        if let Err(e) = self.db.reorder_feeds(&urls) {
+            eprintln!("Failed to save feed order: {}", e);
+        }
+    }
+
+    fn save_settings(&self) {
+        if let Err(e) = self.settings.save() {
+            eprintln!("Failed to save settings: {}", e);
+        }
+    }
+
     fn view(&self) -> Element<'_, Message> {
         use iced::widget::{container, row};
         use iced::Length;
 
         if self.show_settings {
-            ui::settings_view(&self.settings).map(Message::Settings)
+            ui::settings_view(
+                &self.settings,
+                self.storage.as_ref(),
+                &self.opml_path,
+                self.import_plan.as_ref(),
+                self.opml_status.as_deref(),
+            )
+            .map(Message::Settings)
+        } else if self.show_feed_health {
+            ui::feed_health_view(&self.feeds).map(Message::FeedHealth)
+        } else if let Some((page_url, candidates)) = &self.feed_chooser {
+            ui::feed_chooser_view(page_url, candidates).map(Message::FeedChooser)
         } else if let Some(article) = &self.current_article {
             // Show article detail view
-            ui::article_detail_view(article).map(|_| Message::BackToList)
+            let key = (article.feed_url.clone(), article.id.clone());
+            let is_read = self.read_articles.contains(&key);
+            let is_starred = self.starred_articles.contains(&key);
+            ui::article_detail_view(article, self.current_article_body.as_deref(), is_read, is_starred)
+                .map(Message::ArticleDetail)
         } else {
+            let title = match &self.selection {
+                Selection::AllArticles => "All Articles",
+                Selection::Starred => "Starred",
+                Selection::Feed(url) => self
+                    .feeds
+                    .iter()
+                    .find(|f| &f.url == url)
+                    .map_or(url.as_str(), |f| f.title.as_str()),
+                Selection::Folder(id) => self
+                    .folders
+                    .iter()
+                    .find(|f| f.id == *id)
+                    .map_or("Folder", |f| f.name.as_str()),
+                Selection::SavedSearch(id) => self
+                    .saved_searches
+                    .iter()
+                    .find(|s| s.id == *id)
+                    .map_or("Saved Search", |s| s.name.as_str()),
+            };
+
             let content = row![
-                ui::sidebar_view(&self.feeds, &self.feed_input).map(Message::Sidebar),
-                ui::content_view(&self.articles, self.loading, &self.settings, &self.db)
-                    .map(Message::Content),
+                ui::sidebar_view(
+                    &self.feeds,
+                    &self.folders,
+                    &self.saved_searches,
+                    &self.feed_status,
+                    &self.unread_counts,
+                    &self.saved_search_unread,
+                    &self.selection,
+                    &self.feed_input,
+                    &self.folder_input,
+                )
+                .map(Message::Sidebar),
+                ui::content_view(
+                    title,
+                    &self.articles,
+                    &self.feeds,
+                    self.pending_fetches(),
+                    self.selected_feed_error(),
+                    &self.selected_articles,
+                    &self.search,
+                    &self.settings,
+                    &self.read_articles,
+                    &self.starred_articles,
+                )
+                .map(Message::Content),
             ]
             .spacing(0);
 
@@ -182,7 +1149,50 @@ impl App {
         }
     }
 
+    fn subscription(&self) -> Subscription<Message> {
+        let cleanup = iced::time::every(scheduler::CLEANUP_INTERVAL).map(|_| Message::CleanupTick);
+        if self.settings.refresh_paused {
+            cleanup
+        } else {
+            Subscription::batch([iced::time::every(scheduler::TICK).map(Message::SchedulerTick), cleanup])
+        }
+    }
+
     fn theme(&self) -> Theme {
         self.settings.theme.to_iced_theme()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        App::with_db(ArticleDatabase::open(":memory:").unwrap(), AppSettings::default())
+    }
+
+    #[test]
+    fn test_feed_removed_while_refreshing_stays_removed() {
+        let url = "https://a.example/feed".to_string();
+        let mut app = app();
+        app.db.add_feed(&url, "A").unwrap();
+        app.feeds = app.db.list_feeds().unwrap();
+
+        let _ = app.fetch_feed(url.clone());
+        assert_eq!(app.pending_fetches(), 1);
+        app.remove_feed(&url);
+        assert_eq!(app.pending_fetches(), 0);
+
+        let _ = app.update(Message::FeedFetched(url.clone(), Err(FeedError::Timeout(30))));
+        assert!(app.feeds.is_empty());
+        assert!(app.feed_status.is_empty());
+        assert!(app.db.list_feeds().unwrap().is_empty());
+
+        // A feed added in this session and removed before its first fetch lands
+        let _ = app.add_feed(url.clone());
+        assert_eq!(app.pending_fetches(), 1);
+        app.remove_feed(&url);
+        assert_eq!(app.pending_fetches(), 0);
+        assert!(app.unconfirmed_feeds.is_empty());
+    }
+}
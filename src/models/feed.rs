@@ -1,17 +1,100 @@
+use chrono::{DateTime, Utc};
+use std::time::Duration;
 use super::Article;
+use crate::error::FeedError;
 
 #[derive(Debug, Clone)]
 pub struct Feed {
     pub url: String,
     pub title: String,
+    /// Background refresh interval for this feed, overriding the global setting
+    pub refresh_interval_minutes: Option<u64>,
+    /// The server reported the feed as gone (HTTP 410); it is no longer
+    /// refreshed automatically
+    pub dead: bool,
+    pub health: FeedHealth,
+    /// Folder the feed is filed in; `None` lists it at the top level
+    pub folder_id: Option<i64>,
+    /// The website the feed belongs to, as listed by an imported OPML file
+    pub html_url: Option<String>,
 }
 
 impl Feed {
     pub fn new(url: String, title: String) -> Self {
-        Self { url, title }
+        Self {
+            url,
+            title,
+            refresh_interval_minutes: None,
+            dead: false,
+            health: FeedHealth::default(),
+            folder_id: None,
+            html_url: None,
+        }
     }
 }
 
+/// How reliably a feed has been refreshing
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedHealth {
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub consecutive_failures: u32,
+    /// Moving average of fetch latency, weighted towards recent fetches
+    pub avg_latency_ms: Option<f64>,
+}
+
+impl FeedHealth {
+    pub fn record_success(&mut self, at: DateTime<Utc>, latency: Duration) {
+        let latency_ms = latency.as_secs_f64() * 1000.0;
+        self.last_success_at = Some(at);
+        self.consecutive_failures = 0;
+        self.avg_latency_ms = Some(match self.avg_latency_ms {
+            Some(average) => average * 0.8 + latency_ms * 0.2,
+            None => latency_ms,
+        });
+    }
+
+    pub fn record_failure(&mut self, at: DateTime<Utc>, error: &FeedError) {
+        self.last_error = Some(error.to_string());
+        self.last_error_at = Some(at);
+        self.consecutive_failures += 1;
+    }
+
+    /// Whether the most recent refresh failed
+    pub fn is_failing(&self) -> bool {
+        self.consecutive_failures > 0
+    }
+}
+
+/// HTTP cache validators remembered from the last successful fetch of a
+/// feed, sent back so unchanged feeds can be answered with 304 Not Modified
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Outcome of the most recent refresh of a feed
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedStatus {
+    Pending,
+    Ok,
+    /// The server reported that the feed has not changed since the last fetch
+    NotModified,
+    /// The feed was removed by its publisher (HTTP 410)
+    Gone,
+    Error(FeedError),
+}
+
+/// A feed found on a web page the user tried to subscribe to
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedCandidate {
+    pub url: String,
+    /// How the page titles the feed, if it does
+    pub title: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct FeedData {
     pub title: String,
@@ -1,7 +1,17 @@
 pub mod article;
 pub mod feed;
+pub mod folder;
+pub mod opml;
+pub mod search;
 pub mod settings;
+pub mod starred;
+pub mod storage;
 
 pub use article::Article;
-pub use feed::{Feed, FeedData};
-pub use settings::{AppSettings, AppTheme};
+pub use feed::{CacheValidators, Feed, FeedCandidate, FeedData, FeedHealth, FeedStatus};
+pub use folder::Folder;
+pub use opml::{ImportPlan, InvalidOutline, OpmlFeed};
+pub use search::{SavedSearch, SearchResult, SnippetPart};
+pub use settings::{AppSettings, AppTheme, HttpSettings, RetentionSettings};
+pub use starred::StarredArticle;
+pub use storage::StorageStats;
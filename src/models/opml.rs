@@ -0,0 +1,29 @@
+/// A subscription read from an OPML file
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpmlFeed {
+    /// The outline's `xmlUrl`
+    pub url: String,
+    pub title: Option<String>,
+    /// The outline's `htmlUrl`, the website the feed belongs to
+    pub html_url: Option<String>,
+    /// Titles of the folder outlines the feed is nested in, outermost first
+    pub folders: Vec<String>,
+}
+
+/// An outline in an OPML file that can't be subscribed to
+#[derive(Debug, Clone, PartialEq)]
+pub struct InvalidOutline {
+    /// The outline's title, or its `xmlUrl` if it has none
+    pub label: String,
+    pub reason: String,
+}
+
+/// What importing an OPML file would change, worked out before anything is
+/// stored so the user can review it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportPlan {
+    pub new_feeds: Vec<OpmlFeed>,
+    /// Feeds already subscribed to, or listed earlier in the same file
+    pub duplicates: Vec<OpmlFeed>,
+    pub invalid: Vec<InvalidOutline>,
+}
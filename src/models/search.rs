@@ -0,0 +1,34 @@
+use super::Article;
+
+/// An article matching a full-text search
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub article: Article,
+    /// Excerpt of the best matching field around the matched terms
+    pub snippet: Vec<SnippetPart>,
+}
+
+/// A run of snippet text, highlighted where it matched the query
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnippetPart {
+    pub text: String,
+    pub highlighted: bool,
+}
+
+/// A search saved by name and shown in the sidebar as a smart feed. Its
+/// criteria are re-evaluated against the stored articles whenever it is shown.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SavedSearch {
+    pub id: i64,
+    pub name: String,
+    /// Words to match; every word must match unless joined by `OR`. Empty
+    /// matches every article.
+    pub query: String,
+    /// Only articles from this feed
+    pub feed_url: Option<String>,
+    /// Only articles from feeds in this folder or its subfolders
+    pub folder_id: Option<i64>,
+    pub unread_only: bool,
+    /// Only articles from the last this many days
+    pub max_age_days: Option<u32>,
+}
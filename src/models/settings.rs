@@ -1,6 +1,12 @@
 use iced::Theme;
+use serde::de::IntoDeserializer;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::path::PathBuf;
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::feed_engine::MIN_TIMEOUT_SECS;
+use crate::scheduler::MIN_REFRESH_INTERVAL_MINUTES;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum AppTheme {
     CatppuccinMocha,
     CatppuccinMacchiato,
@@ -103,11 +109,20 @@ impl AppTheme {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppSettings {
+    #[serde(deserialize_with = "theme_or_default")]
     pub theme: AppTheme,
     pub show_images: bool,
     pub show_excerpts: bool,
+    /// Minutes between background refreshes of each feed
+    #[serde(deserialize_with = "refresh_interval_in_range")]
+    pub refresh_interval_minutes: u64,
+    /// Suspend background refreshes; manual refresh still works
+    pub refresh_paused: bool,
+    pub http: HttpSettings,
+    pub retention: RetentionSettings,
 }
 
 impl Default for AppSettings {
@@ -116,6 +131,172 @@ impl Default for AppSettings {
             theme: AppTheme::CatppuccinMocha,
             show_images: true,
             show_excerpts: true,
+            refresh_interval_minutes: 30,
+            refresh_paused: false,
+            http: HttpSettings::default(),
+            retention: RetentionSettings::default(),
+        }
+    }
+}
+
+/// How feeds are downloaded
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpSettings {
+    /// Sent as the User-Agent header; unset identifies as `Czytaj/<version>`
+    pub user_agent: Option<String>,
+    #[serde(deserialize_with = "timeout_in_range")]
+    pub connect_timeout_secs: u64,
+    /// Longest wait for the server to send more of a response; a slow but
+    /// steady download of a large feed isn't cut off
+    #[serde(alias = "request_timeout_secs", deserialize_with = "timeout_in_range")]
+    pub read_timeout_secs: u64,
+    pub max_redirects: usize,
+}
+
+impl Default for HttpSettings {
+    fn default() -> Self {
+        Self {
+            user_agent: None,
+            connect_timeout_secs: 10,
+            read_timeout_secs: 30,
+            max_redirects: 10,
         }
     }
 }
+
+/// How long stored articles are kept. Starred articles are never purged.
+/// Both limits are off until the user sets them, so upgrading never throws
+/// away the history of an existing install.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    /// Purge articles older than this many days; 0 keeps them forever
+    pub max_age_days: u32,
+    /// Keep this many of each feed's newest articles; 0 keeps them all
+    pub max_articles_per_feed: u32,
+}
+
+impl AppSettings {
+    /// Load settings from disk, falling back to defaults if the file is
+    /// missing or unreadable
+    pub fn load() -> Self {
+        let path = Self::config_path();
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).unwrap_or_else(|e| {
+                eprintln!("Failed to parse settings file {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                eprintln!("Failed to read settings file {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Write settings to disk
+    pub fn save(&self) -> std::io::Result<()> {
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(Self::config_path(), contents)
+    }
+
+    /// Get the path to the settings file
+    fn config_path() -> PathBuf {
+        // Use user's config directory or fallback to current directory
+        let mut path = dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."));
+        path.push("czytaj");
+        std::fs::create_dir_all(&path).ok();
+        path.push("settings.toml");
+        path
+    }
+}
+
+/// Deserialize a theme, falling back to the default for names this build
+/// doesn't know (e.g. a theme written by the other front end)
+fn theme_or_default<'de, D>(deserializer: D) -> Result<AppTheme, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let name = String::deserialize(deserializer)?;
+    let theme: Result<AppTheme, serde::de::value::Error> =
+        AppTheme::deserialize(name.as_str().into_deserializer());
+    Ok(theme.unwrap_or_else(|_| AppSettings::default().theme))
+}
+
+/// Deserialize the refresh interval, raising values too short to use (e.g.
+/// 0 in a hand-edited file) to the shortest one accepted
+fn refresh_interval_in_range<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(u64::deserialize(deserializer)?.max(MIN_REFRESH_INTERVAL_MINUTES))
+}
+
+/// Deserialize a timeout, raising 0, which would fail every fetch, to the
+/// shortest one accepted
+fn timeout_in_range<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(u64::deserialize(deserializer)?.max(MIN_TIMEOUT_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let settings = AppSettings {
+            theme: AppTheme::Nord,
+            show_images: false,
+            show_excerpts: true,
+            refresh_interval_minutes: 15,
+            refresh_paused: true,
+            http: HttpSettings {
+                user_agent: Some("Custom/1.0".to_string()),
+                ..HttpSettings::default()
+            },
+            retention: RetentionSettings { max_age_days: 0, max_articles_per_feed: 200 },
+        };
+        let serialized = toml::to_string_pretty(&settings).unwrap();
+        let restored: AppSettings = toml::from_str(&serialized).unwrap();
+        assert_eq!(restored.theme, settings.theme);
+        assert_eq!(restored.show_images, settings.show_images);
+        assert_eq!(restored.show_excerpts, settings.show_excerpts);
+        assert_eq!(restored.refresh_interval_minutes, settings.refresh_interval_minutes);
+        assert_eq!(restored.refresh_paused, settings.refresh_paused);
+        assert_eq!(restored.http, settings.http);
+        assert_eq!(restored.retention, settings.retention);
+    }
+
+    #[test]
+    fn test_missing_and_unknown_keys_use_defaults() {
+        let restored: AppSettings = toml::from_str(
+            "show_images = false\ntheme = \"NoSuchTheme\"\nsome_future_option = 3\n\
+             [http]\nconnect_timeout_secs = 5\n"
+        ).unwrap();
+        assert!(!restored.show_images);
+        assert!(restored.show_excerpts);
+        assert_eq!(restored.refresh_interval_minutes, 30);
+        assert_eq!(restored.theme, AppSettings::default().theme);
+        assert_eq!(restored.http.connect_timeout_secs, 5);
+        assert_eq!(restored.http.read_timeout_secs, 30);
+        assert_eq!(restored.http.user_agent, None);
+
+        let restored: AppSettings = toml::from_str("refresh_interval_minutes = 0").unwrap();
+        assert_eq!(restored.refresh_interval_minutes, MIN_REFRESH_INTERVAL_MINUTES);
+
+        // Timeouts of 0 are raised, and files written before the read
+        // timeout was split out keep theirs
+        let restored: AppSettings =
+            toml::from_str("[http]\nconnect_timeout_secs = 0\nrequest_timeout_secs = 0\n").unwrap();
+        assert_eq!(restored.http.connect_timeout_secs, MIN_TIMEOUT_SECS);
+        assert_eq!(restored.http.read_timeout_secs, MIN_TIMEOUT_SECS);
+        let restored: AppSettings = toml::from_str("[http]\nrequest_timeout_secs = 60\n").unwrap();
+        assert_eq!(restored.http.read_timeout_secs, 60);
+    }
+}
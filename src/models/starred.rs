@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+
+use super::Article;
+
+/// Snapshot of an article saved for later. It is kept as it was when
+/// starred, even after the feed drops the article or is unsubscribed.
+#[derive(Debug, Clone)]
+#[allow(dead_code)] // Feed title and star date are only shown by the Tauri frontend
+pub struct StarredArticle {
+    pub article: Article,
+    /// Title of the feed at the time the article was starred
+    pub feed_title: String,
+    pub starred_at: DateTime<Utc>,
+}
@@ -1,8 +1,24 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Article, Feed, FeedData, AppSettings } from "../types";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type {
+  AddFeedResult,
+  Article,
+  Feed,
+  FeedData,
+  Folder,
+  ImportPlan,
+  FeedMovedEvent,
+  FeedStatusEvent,
+  AppSettings,
+  SavedSearch,
+  SearchResult,
+  StarredArticle,
+  StorageStats,
+} from "../types";
 
 export const api = {
-  async addFeed(url: string): Promise<FeedData> {
+  /** Accepts a feed or a web page linking to feeds */
+  async addFeed(url: string): Promise<AddFeedResult> {
     return await invoke("add_feed", { url });
   },
 
@@ -10,22 +26,193 @@ export const api = {
     return await invoke("refresh_feed", { url });
   },
 
+  async removeFeed(url: string): Promise<void> {
+    return await invoke("remove_feed", { url });
+  },
+
+  async reorderFeeds(urls: string[]): Promise<void> {
+    return await invoke("reorder_feeds", { urls });
+  },
+
+  async getArticles(feedUrl: string): Promise<Article[]> {
+    return await invoke("get_articles", { feedUrl });
+  },
+
+  async getTimeline(): Promise<Article[]> {
+    return await invoke("get_timeline");
+  },
+
+  async listFolders(): Promise<Folder[]> {
+    return await invoke("list_folders");
+  },
+
+  async createFolder(name: string, parentId: number | null): Promise<Folder> {
+    return await invoke("create_folder", { name, parentId });
+  },
+
+  async renameFolder(id: number, name: string): Promise<void> {
+    return await invoke("rename_folder", { id, name });
+  },
+
+  /** Feeds move to the parent folder; subfolders become top-level */
+  async deleteFolder(id: number): Promise<void> {
+    return await invoke("delete_folder", { id });
+  },
+
+  async setFolderCollapsed(id: number, collapsed: boolean): Promise<void> {
+    return await invoke("set_folder_collapsed", { id, collapsed });
+  },
+
+  async setFeedFolder(url: string, folderId: number | null): Promise<void> {
+    return await invoke("set_feed_folder", { url, folderId });
+  },
+
+  /** Articles of a folder's feeds, including its subfolders */
+  async getFolderTimeline(id: number): Promise<Article[]> {
+    return await invoke("get_folder_timeline", { id });
+  },
+
+  /** Unread articles per feed URL */
+  async getUnreadCounts(): Promise<Record<string, number>> {
+    return await invoke("get_unread_counts");
+  },
+
+  async getTotalUnread(): Promise<number> {
+    return await invoke("get_total_unread");
+  },
+
+  /** Each mark operation resolves to how many articles were unread */
+  async markFeedRead(url: string): Promise<number> {
+    return await invoke("mark_feed_read", { url });
+  },
+
+  async markAllRead(): Promise<number> {
+    return await invoke("mark_all_read");
+  },
+
+  async markReadOlderThan(days: number): Promise<number> {
+    return await invoke("mark_read_older_than", { days });
+  },
+
+  /** Starts refreshing every feed; progress arrives through onFeedStatus */
+  async refreshAllFeeds(): Promise<void> {
+    return await invoke("refresh_all_feeds");
+  },
+
+  async setFeedRefreshInterval(url: string, minutes: number | null): Promise<void> {
+    return await invoke("set_feed_refresh_interval", { url, minutes });
+  },
+
+  /** Dry run of an OPML import; nothing is stored */
+  async previewOpmlImport(contents: string): Promise<ImportPlan> {
+    return await invoke("preview_opml_import", { contents });
+  },
+
+  async importOpml(contents: string): Promise<ImportPlan> {
+    return await invoke("import_opml", { contents });
+  },
+
+  async exportOpml(): Promise<string> {
+    return await invoke("export_opml");
+  },
+
+  async onFeedStatus(handler: (event: FeedStatusEvent) => void): Promise<UnlistenFn> {
+    return await listen<FeedStatusEvent>("feed-status", (event) => handler(event.payload));
+  },
+
+  async onFeedMoved(handler: (event: FeedMovedEvent) => void): Promise<UnlistenFn> {
+    return await listen<FeedMovedEvent>("feed-moved", (event) => handler(event.payload));
+  },
+
   async getFeeds(): Promise<Feed[]> {
     return await invoke("get_feeds");
   },
 
-  async markArticleViewed(url: string, title: string): Promise<void> {
-    return await invoke("mark_article_viewed", { url, title });
+  async markArticleViewed(feedUrl: string, entryId: string, title: string): Promise<void> {
+    return await invoke("mark_article_viewed", { feedUrl, entryId, title });
+  },
+
+  async markArticleUnread(feedUrl: string, entryId: string): Promise<void> {
+    return await invoke("mark_article_unread", { feedUrl, entryId });
   },
 
-  async isArticleViewed(url: string): Promise<boolean> {
-    return await invoke("is_article_viewed", { url });
+  /** Resolves to how many of the `[feedUrl, entryId]` articles changed */
+  async setArticlesRead(keys: [string, string][], read: boolean): Promise<number> {
+    return await invoke("set_articles_read", { keys, read });
   },
 
-  async getViewedArticles(): Promise<string[]> {
+  /**
+   * Best matches first; optionally within one feed or folder (with its
+   * subfolders), only unread articles and only the last `maxAgeDays` days
+   */
+  async searchArticles(
+    query: string,
+    feed: string | null,
+    folder: number | null,
+    unreadOnly: boolean,
+    maxAgeDays: number | null,
+  ): Promise<SearchResult[]> {
+    return await invoke("search_articles", { query, feed, folder, unreadOnly, maxAgeDays });
+  },
+
+  async listSavedSearches(): Promise<SavedSearch[]> {
+    return await invoke("list_saved_searches");
+  },
+
+  /** Resolves to the new search's ID; the `id` passed in is ignored */
+  async createSavedSearch(search: SavedSearch): Promise<number> {
+    return await invoke("create_saved_search", { search });
+  },
+
+  async deleteSavedSearch(id: number): Promise<void> {
+    return await invoke("delete_saved_search", { id });
+  },
+
+  /** Articles currently matching the search, newest first */
+  async getSavedSearchArticles(id: number): Promise<Article[]> {
+    return await invoke("get_saved_search_articles", { id });
+  },
+
+  /** Unread articles matching each saved search, keyed by ID */
+  async getSavedSearchUnreadCounts(): Promise<Record<number, number>> {
+    return await invoke("get_saved_search_unread_counts");
+  },
+
+  /** Full body of an article as sanitized HTML, if its feed has one */
+  async getArticleContent(feedUrl: string, entryId: string): Promise<string | null> {
+    return await invoke("get_article_content", { feedUrl, entryId });
+  },
+
+  async starArticle(article: Article): Promise<void> {
+    return await invoke("star_article", { article });
+  },
+
+  async unstarArticle(feedUrl: string, entryId: string): Promise<void> {
+    return await invoke("unstar_article", { feedUrl, entryId });
+  },
+
+  /** Most recently starred first */
+  async listStarred(): Promise<StarredArticle[]> {
+    return await invoke("list_starred");
+  },
+
+  async isArticleViewed(feedUrl: string, entryId: string): Promise<boolean> {
+    return await invoke("is_article_viewed", { feedUrl, entryId });
+  },
+
+  async getViewedArticles(): Promise<[string, string][]> {
     return await invoke("get_viewed_articles");
   },
 
+  async getStorageStats(): Promise<StorageStats> {
+    return await invoke("get_storage_stats");
+  },
+
+  /** Purge old articles and compact the database; resolves to the storage used afterwards */
+  async cleanUpStorage(): Promise<StorageStats> {
+    return await invoke("clean_up_storage");
+  },
+
   async getSettings(): Promise<AppSettings> {
     return await invoke("get_settings");
   },
@@ -34,3 +221,8 @@ export const api = {
     return await invoke("update_settings", { newSettings });
   },
 };
+
+/** Identity of an article across feeds, used as a key for read state */
+export function articleKey(feedUrl: string, entryId: string): string {
+  return `${feedUrl}\n${entryId}`;
+}
@@ -0,0 +1,88 @@
+import type { Article, FeedError } from "../types";
+
+/** Format a date relative to now, e.g. "5m ago", "3h ago", "2d ago" */
+export function formatRelative(iso: string, now: Date = new Date()): string {
+  const date = new Date(iso);
+  const seconds = Math.floor((now.getTime() - date.getTime()) / 1000);
+
+  if (seconds < 60) {
+    return "just now";
+  } else if (seconds < 3600) {
+    return `${Math.floor(seconds / 60)}m ago`;
+  } else if (seconds < 86400) {
+    return `${Math.floor(seconds / 3600)}h ago`;
+  } else if (seconds < 7 * 86400) {
+    return `${Math.floor(seconds / 86400)}d ago`;
+  }
+  return date.toLocaleDateString(undefined, { month: "short", day: "numeric", year: "numeric" });
+}
+
+/** "Author · 3h ago" line for an article, or null if it has neither */
+export function byline(article: Article): string | null {
+  const parts: string[] = [];
+  if (article.authors.length > 0) {
+    parts.push(article.authors.join(", "));
+  }
+  const date = article.published ?? article.updated;
+  if (date) {
+    parts.push(formatRelative(date));
+  }
+  return parts.length > 0 ? parts.join(" · ") : null;
+}
+
+/** Choices for how often feeds are refreshed, globally or one by one */
+export const REFRESH_INTERVALS: [number, string][] = [
+  [5, "Every 5 minutes"],
+  [15, "Every 15 minutes"],
+  [30, "Every 30 minutes"],
+  [60, "Every hour"],
+  [180, "Every 3 hours"],
+  [720, "Every 12 hours"],
+];
+
+function isFeedError(error: unknown): error is FeedError {
+  return typeof error === "object" && error !== null && "kind" in error;
+}
+
+/** Human readable explanation of a failed command or feed refresh */
+export function describeError(error: unknown): string {
+  if (!isFeedError(error)) {
+    return String(error);
+  }
+  switch (error.kind) {
+    case "network":
+      return `Couldn't reach the server: ${error.detail}`;
+    case "timeout":
+      return `The server didn't respond within ${error.detail} seconds`;
+    case "http_status":
+      switch (error.detail) {
+        case 401:
+        case 403:
+          return `The server refused access to this feed (HTTP ${error.detail})`;
+        case 404:
+          return "No feed was found at this address (HTTP 404)";
+        case 410:
+          return "This feed no longer exists (HTTP 410)";
+        case 429:
+          return "The server is limiting requests, try again later (HTTP 429)";
+        default:
+          return error.detail >= 500
+            ? `The server ran into a problem (HTTP ${error.detail})`
+            : `The server answered with HTTP ${error.detail}`;
+      }
+    case "too_large":
+      return `The feed is larger than the ${Math.floor(error.detail / (1024 * 1024))} MB limit`;
+    case "parse":
+      return `This address doesn't serve a valid RSS or Atom feed: ${error.detail}`;
+    case "invalid_url":
+      return `Invalid feed address: ${error.detail}`;
+    case "database":
+      return `Couldn't update the local database: ${error.detail}`;
+    case "invalid_opml":
+      return `This file isn't a valid subscription list: ${error.detail}`;
+    case "invalid_settings":
+      return `These HTTP settings can't be used: ${error.detail}`;
+    case "settings":
+      return `Couldn't save the settings: ${error.detail}`;
+  }
+}
@@ -1,13 +1,107 @@
 export interface Article {
+  feed_url: string;
+  id: string;
   title: string;
   link: string;
+  /** Short plain-text preview for article lists */
   excerpt: string | null;
+  /** Full body as sanitized HTML; lists leave it out, see api.getArticleContent */
+  content?: string | null;
   image_url: string | null;
+  published: string | null;
+  updated: string | null;
+  authors: string[];
+  categories: string[];
+}
+
+/** Snapshot of an article saved for later; it outlives the feed */
+export interface StarredArticle extends Article {
+  /** Title of the feed when the article was starred */
+  feed_title: string;
+  starred_at: string;
+}
+
+/** An article matching a full-text search */
+export interface SearchResult extends Article {
+  /** Text around the matches, with the matched runs highlighted */
+  snippet: SnippetPart[];
+}
+
+export interface SnippetPart {
+  text: string;
+  highlighted: boolean;
+}
+
+/** A search saved by name and shown as a smart feed */
+export interface SavedSearch {
+  id: number;
+  name: string;
+  /** Words must all match unless joined by OR; empty matches every article */
+  query: string;
+  feed_url: string | null;
+  /** Limits the search to feeds in this folder and its subfolders */
+  folder_id: number | null;
+  unread_only: boolean;
+  max_age_days: number | null;
 }
 
 export interface Feed {
   url: string;
   title: string;
+  /** Overrides the global refresh interval when set */
+  refresh_interval_minutes: number | null;
+  /** The server reported the feed as gone (HTTP 410) */
+  dead: boolean;
+  health: FeedHealth;
+  folder_id: number | null;
+  /** The website the feed belongs to, as listed by an imported OPML file */
+  html_url: string | null;
+}
+
+/** Folders nest one level deep: top-level folders may hold subfolders */
+export interface Folder {
+  id: number;
+  name: string;
+  parent_id: number | null;
+  collapsed: boolean;
+}
+
+/** How reliably a feed has been refreshing */
+export interface FeedHealth {
+  last_success_at: string | null;
+  last_error: string | null;
+  last_error_at: string | null;
+  consecutive_failures: number;
+  /** Moving average of fetch latency, weighted towards recent fetches */
+  avg_latency_ms: number | null;
+}
+
+export type FeedStatus =
+  | { status: "pending" }
+  | { status: "ok" }
+  | { status: "not_modified" }
+  | { status: "gone" }
+  | { status: "error"; message: FeedError };
+
+/** Why a command failed, most often fetching or storing a feed; mirrors the backend's FeedError */
+export type FeedError =
+  | { kind: "network"; detail: string }
+  | { kind: "timeout"; detail: number }
+  | { kind: "http_status"; detail: number }
+  | { kind: "too_large"; detail: number }
+  | { kind: "parse"; detail: string }
+  | { kind: "invalid_url"; detail: string }
+  | { kind: "database"; detail: string }
+  | { kind: "invalid_opml"; detail: string }
+  | { kind: "invalid_settings"; detail: string }
+  | { kind: "settings"; detail: string };
+
+export type FeedStatusEvent = FeedStatus & { url: string };
+
+/** A subscription followed a permanent redirect */
+export interface FeedMovedEvent {
+  from: string;
+  to: string;
 }
 
 export interface FeedData {
@@ -15,10 +109,73 @@ export interface FeedData {
   articles: Article[];
 }
 
+/** A feed found on a web page the user tried to subscribe to */
+export interface FeedCandidate {
+  url: string;
+  title: string | null;
+}
+
+/** What adding a feed did: subscribed, or found several feeds to pick from */
+export type AddFeedResult =
+  | { result: "subscribed"; url: string; feed: FeedData }
+  | { result: "choose"; candidates: FeedCandidate[] };
+
+/** A subscription read from an OPML file */
+export interface OpmlFeed {
+  url: string;
+  title: string | null;
+  html_url: string | null;
+  /** Titles of the folders the feed is nested in, outermost first */
+  folders: string[];
+}
+
+export interface InvalidOutline {
+  label: string;
+  reason: string;
+}
+
+/** What importing an OPML file changes, reported before anything is stored */
+export interface ImportPlan {
+  new_feeds: OpmlFeed[];
+  duplicates: OpmlFeed[];
+  invalid: InvalidOutline[];
+}
+
 export type Theme = "Light" | "Dark";
 
 export interface AppSettings {
   theme: Theme;
   show_images: boolean;
   show_excerpts: boolean;
+  refresh_interval_minutes: number;
+  refresh_paused: boolean;
+  http: HttpSettings;
+  retention: RetentionSettings;
+}
+
+/** How long stored articles are kept; starred articles are never purged */
+export interface RetentionSettings {
+  /** 0 keeps articles forever */
+  max_age_days: number;
+  /** 0 keeps every article of a feed */
+  max_articles_per_feed: number;
+}
+
+export interface StorageStats {
+  database_bytes: number;
+  /** Part of the file that compacting would give back */
+  free_bytes: number;
+  articles: number;
+  starred: number;
+  /** Articles marked as read */
+  read_markers: number;
+}
+
+export interface HttpSettings {
+  /** Unset identifies as Czytaj/<version> */
+  user_agent: string | null;
+  connect_timeout_secs: number;
+  /** Longest wait for more of a response, not for the whole download */
+  read_timeout_secs: number;
+  max_redirects: number;
 }
@@ -1,13 +1,23 @@
-use iced::widget::{button, column, container, scrollable, text, Column};
+use iced::widget::{button, column, container, horizontal_space, row, scrollable, text, Column};
 use iced::{Element, Length, Padding, Shadow, Theme};
 use crate::models::Article;
+use crate::ui::content::{byline, tags};
 
 #[derive(Debug, Clone)]
 pub enum ArticleDetailMessage {
     BackToList,
+    ToggleRead,
+    ToggleStar,
 }
 
-pub fn article_detail_view<'a>(article: &'a Article) -> Element<'a, ArticleDetailMessage> {
+/// Detail view of `article`, showing `body`, its content as plain text, if
+/// the feed provided one
+pub fn article_detail_view<'a>(
+    article: &'a Article,
+    body: Option<&'a str>,
+    is_read: bool,
+    is_starred: bool,
+) -> Element<'a, ArticleDetailMessage> {
     let mut content = Column::new()
         .spacing(24)
         .padding(Padding::from([32, 40]))
@@ -20,46 +30,19 @@ pub fn article_detail_view<'a>(article: &'a Article) -> Element<'a, ArticleDetai
     )
     .on_press(ArticleDetailMessage::BackToList)
     .padding(Padding::from([12, 24]))
-    .style(|theme: &Theme, status| {
-        let palette = theme.extended_palette();
-        let base = button::Style {
-            background: Some(iced::Background::Color(palette.background.weak.color)),
-            text_color: palette.background.base.text,
-            border: iced::Border {
-                radius: 8.0.into(),
-                ..Default::default()
-            },
-            shadow: Shadow {
-                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
-                offset: iced::Vector::new(0.0, 2.0),
-                blur_radius: 4.0,
-            },
-        };
-        
-        match status {
-            button::Status::Hovered => button::Style {
-                background: Some(iced::Background::Color(palette.background.strong.color)),
-                shadow: Shadow {
-                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.15),
-                    offset: iced::Vector::new(0.0, 4.0),
-                    blur_radius: 8.0,
-                },
-                ..base
-            },
-            button::Status::Pressed => button::Style {
-                background: Some(iced::Background::Color(palette.secondary.weak.color)),
-                shadow: Shadow {
-                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.08),
-                    offset: iced::Vector::new(0.0, 1.0),
-                    blur_radius: 2.0,
-                },
-                ..base
-            },
-            _ => base,
-        }
-    });
+    .style(detail_button_style);
     
-    content = content.push(back_button);
+    let read_button = button(text(if is_read { "○ Mark as unread" } else { "● Mark as read" }).size(16))
+        .on_press(ArticleDetailMessage::ToggleRead)
+        .padding(Padding::from([12, 24]))
+        .style(detail_button_style);
+
+    let star_button = button(text(if is_starred { "★ Starred" } else { "☆ Star" }).size(16))
+        .on_press(ArticleDetailMessage::ToggleStar)
+        .padding(Padding::from([12, 24]))
+        .style(detail_button_style);
+
+    content = content.push(row![back_button, horizontal_space(), star_button, read_button].spacing(12));
 
     // Article title with theme colors
     content = content.push(
@@ -72,6 +55,31 @@ pub fn article_detail_view<'a>(article: &'a Article) -> Element<'a, ArticleDetai
             })
     );
 
+    // Author, date and categories
+    if let Some(byline) = byline(article, chrono::Utc::now()) {
+        content = content.push(
+            text(byline)
+                .size(15)
+                .style(|theme: &Theme| {
+                    text::Style {
+                        color: Some(theme.extended_palette().background.strong.text),
+                    }
+                })
+        );
+    }
+
+    if !article.categories.is_empty() {
+        content = content.push(
+            text(tags(&article.categories))
+                .size(14)
+                .style(|theme: &Theme| {
+                    text::Style {
+                        color: Some(theme.extended_palette().secondary.base.color),
+                    }
+                })
+        );
+    }
+
     // Material-style divider
     content = content.push(
         container(column![])
@@ -105,18 +113,22 @@ pub fn article_detail_view<'a>(article: &'a Article) -> Element<'a, ArticleDetai
         );
     }
 
-    // Article excerpt/content with better styling
-    if let Some(excerpt) = &article.excerpt {
-        content = content.push(
-            text(excerpt)
-                .size(18)
-                .style(|theme: &Theme| {
-                    text::Style {
-                        color: Some(theme.palette().text),
-                    }
-                })
-                .line_height(1.6)
-        );
+    // Whole article when the feed has its body, otherwise the excerpt
+    if let Some(body) = body.or(article.excerpt.as_deref()) {
+        let mut paragraphs = Column::new().spacing(16);
+        for paragraph in body.split("\n\n") {
+            paragraphs = paragraphs.push(
+                text(paragraph.to_string())
+                    .size(18)
+                    .style(|theme: &Theme| {
+                        text::Style {
+                            color: Some(theme.palette().text),
+                        }
+                    })
+                    .line_height(1.6)
+            );
+        }
+        content = content.push(paragraphs);
     } else {
         content = content.push(
             text("No content preview available.")
@@ -187,3 +199,43 @@ pub fn article_detail_view<'a>(article: &'a Article) -> Element<'a, ArticleDetai
         })
         .into()
 }
+
+/// Material-style raised button at the top of the detail view
+fn detail_button_style(theme: &Theme, status: button::Status) -> button::Style {
+    let palette = theme.extended_palette();
+    let base = button::Style {
+        background: Some(iced::Background::Color(palette.background.weak.color)),
+        text_color: palette.background.base.text,
+        border: iced::Border {
+            radius: 8.0.into(),
+            ..Default::default()
+        },
+        shadow: Shadow {
+            color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+            offset: iced::Vector::new(0.0, 2.0),
+            blur_radius: 4.0,
+        },
+    };
+    
+    match status {
+        button::Status::Hovered => button::Style {
+            background: Some(iced::Background::Color(palette.background.strong.color)),
+            shadow: Shadow {
+                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.15),
+                offset: iced::Vector::new(0.0, 4.0),
+                blur_radius: 8.0,
+            },
+            ..base
+        },
+        button::Status::Pressed => button::Style {
+            background: Some(iced::Background::Color(palette.secondary.weak.color)),
+            shadow: Shadow {
+                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.08),
+                offset: iced::Vector::new(0.0, 1.0),
+                blur_radius: 2.0,
+            },
+            ..base
+        },
+        _ => base,
+    }
+}
@@ -1,32 +1,93 @@
-use iced::widget::{button, column, container, scrollable, text, Column};
+use iced::widget::{
+    button, checkbox, column, container, horizontal_space, pick_list, rich_text, row, scrollable, span, text, text_input,
+    Column,
+};
+use std::collections::HashSet;
 use iced::{Element, Length, Padding, Shadow, Theme};
-use crate::models::{Article, AppSettings};
-use crate::db::ArticleDatabase;
+use crate::models::{Article, AppSettings, Feed, SearchResult};
+use crate::error::FeedError;
+use crate::utils::format_relative;
 
 #[derive(Debug, Clone)]
 pub enum ContentMessage {
-    ArticleClicked(Article),
+    ArticleClicked(Box<Article>),
+    /// Mark every article in the current view as read
+    MarkAllRead,
+    /// Mark articles older than this many days as read, in every feed
+    MarkOlderRead(u32),
+    /// Flip the read state of the article with this `(feed_url, entry_id)` key
+    ToggleRead(String, String),
+    /// Star the article, or unstar it if it is starred
+    ToggleStar(Box<Article>),
+    /// Add the article to the selection, or take it out
+    ToggleSelected(String, String),
+    /// Mark the selected articles as read (`true`) or unread
+    MarkSelected(bool),
+    ClearSelection,
+    SearchChanged(String),
+    Search,
+    ToggleUnreadOnly(bool),
+    /// Only search articles from the last this many days, or any time
+    MaxAgeChanged(Option<u32>),
+    ClearSearch,
+    SaveNameChanged(String),
+    /// Save the current search as a smart feed under the typed name
+    SaveSearch,
 }
 
+/// Full-text search typed into the content header
+#[derive(Debug, Default)]
+pub struct Search {
+    pub query: String,
+    /// Only search articles that haven't been read
+    pub unread_only: bool,
+    /// Only search articles published in the last this many days
+    pub max_age_days: Option<u32>,
+    /// Results of the last search; `None` shows the article list instead
+    pub results: Option<Vec<SearchResult>>,
+    /// Name to save the search under
+    pub save_name: String,
+}
+
+/// Age in days past which "mark older as read" applies
+const MARK_OLDER_THAN_DAYS: u32 = 7;
+
+#[allow(clippy::too_many_arguments)]
 pub fn content_view<'a>(
+    title: &'a str,
     articles: &'a [Article],
-    loading: bool,
+    feeds: &'a [Feed],
+    pending_fetches: usize,
+    error: Option<&'a FeedError>,
+    selected: &'a HashSet<(String, String)>,
+    search: &'a Search,
     settings: &'a AppSettings,
-    db: &'a ArticleDatabase,
+    read: &'a HashSet<(String, String)>,
+    starred: &'a HashSet<(String, String)>,
 ) -> Element<'a, ContentMessage> {
     let mut article_list = Column::new()
         .spacing(20)
         .padding(Padding::from([24, 32]));
 
-    // Modern header with theme-aware colors
+    // Modern header with theme-aware colors and read-state actions
     article_list = article_list.push(
-        text("Articles")
-            .size(34)
-            .style(|theme: &Theme| {
-                text::Style {
-                    color: Some(theme.palette().text),
-                }
-            })
+        row![
+            text(title)
+                .size(34)
+                .style(|theme: &Theme| {
+                    text::Style {
+                        color: Some(theme.palette().text),
+                    }
+                }),
+            horizontal_space(),
+            header_button("✓ Mark as read", ContentMessage::MarkAllRead),
+            header_button(
+                format!("✓ Older than {} days", MARK_OLDER_THAN_DAYS),
+                ContentMessage::MarkOlderRead(MARK_OLDER_THAN_DAYS),
+            ),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center)
     );
 
     // Material-style divider
@@ -46,7 +107,116 @@ pub fn content_view<'a>(
             })
     );
 
-    if loading {
+    // Search box; searches the selected feed or folder, or every feed
+    let mut search_bar = row![
+        text_input("Search articles...", &search.query)
+            .on_input(ContentMessage::SearchChanged)
+            .on_submit(ContentMessage::Search)
+            .padding(10)
+            .size(15),
+        checkbox("Unread only", search.unread_only).on_toggle(ContentMessage::ToggleUnreadOnly),
+        pick_list(&MaxAge::ALL[..], Some(MaxAge(search.max_age_days)), |age: MaxAge| {
+            ContentMessage::MaxAgeChanged(age.0)
+        }),
+    ]
+    .spacing(12)
+    .align_y(iced::Alignment::Center);
+    if search.results.is_some() {
+        search_bar = search_bar.push(header_button("Clear", ContentMessage::ClearSearch));
+    }
+    article_list = article_list.push(search_bar);
+    if search.results.is_some() {
+        article_list = article_list.push(
+            row![
+                text_input("Name this search...", &search.save_name)
+                    .on_input(ContentMessage::SaveNameChanged)
+                    .on_submit(ContentMessage::SaveSearch)
+                    .padding(8)
+                    .size(14),
+                header_button("🔎 Save as smart feed", ContentMessage::SaveSearch),
+            ]
+            .spacing(12)
+            .align_y(iced::Alignment::Center)
+        );
+    }
+
+    // Bulk actions over the selected articles
+    if !selected.is_empty() {
+        article_list = article_list.push(
+            row![
+                text(format!("{} selected", selected.len())).size(15),
+                horizontal_space(),
+                header_button("Mark read", ContentMessage::MarkSelected(true)),
+                header_button("Mark unread", ContentMessage::MarkSelected(false)),
+                header_button("Clear", ContentMessage::ClearSelection),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+        );
+    }
+
+    // Why the selected feed failed to refresh; stored articles stay readable
+    if let Some(error) = error {
+        article_list = article_list.push(
+            container(
+                text(format!("⚠ {}", error))
+                    .size(14)
+                    .style(|theme: &Theme| {
+                        text::Style {
+                            color: Some(theme.extended_palette().danger.base.text),
+                        }
+                    })
+            )
+            .width(Length::Fill)
+            .padding(Padding::from([12, 16]))
+            .style(|theme: &Theme| {
+                container::Style {
+                    background: Some(iced::Background::Color(theme.extended_palette().danger.base.color)),
+                    border: iced::Border {
+                        radius: 8.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+            })
+        );
+    }
+
+    // Keep showing stored articles while feeds refresh in the background
+    if pending_fetches > 0 && !articles.is_empty() {
+        article_list = article_list.push(
+            text(format!(
+                "⟳ Refreshing {} feed{}...",
+                pending_fetches,
+                if pending_fetches == 1 { "" } else { "s" }
+            ))
+            .size(14)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.extended_palette().secondary.base.color),
+                }
+            })
+        );
+    }
+
+    if let Some(results) = &search.results {
+        article_list = article_list.push(
+            text(match results.len() {
+                0 => "No matching articles".to_string(),
+                1 => "1 matching article".to_string(),
+                n => format!("{} matching articles", n),
+            })
+            .size(14)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.extended_palette().background.strong.text),
+                }
+            })
+        );
+        for result in results {
+            article_list = article_list.push(search_result_card(result, feeds));
+        }
+    } else if pending_fetches > 0 && articles.is_empty() {
         // Animated loading state
         article_list = article_list.push(
             container(
@@ -91,9 +261,13 @@ pub fn content_view<'a>(
             .width(Length::Fill)
         );
     } else {
+        let now = chrono::Utc::now();
+
         // Article cards with Material Design elevation
         for article in articles {
-            let is_viewed = db.is_viewed(&article.link).unwrap_or(false);
+            let key = (article.feed_url.clone(), article.id.clone());
+            let is_viewed = read.contains(&key);
+            let is_starred = starred.contains(&key);
             
             let mut article_content = Column::new().spacing(10);
 
@@ -112,6 +286,28 @@ pub fn content_view<'a>(
                     })
             );
 
+            // Source feed, author and relative publication date
+            let source = feeds
+                .iter()
+                .find(|f| f.url == article.feed_url)
+                .map(|f| f.title.as_str());
+            let byline = match (source, byline(article, now)) {
+                (Some(source), Some(byline)) => Some(format!("{} · {}", source, byline)),
+                (Some(source), None) => Some(source.to_string()),
+                (None, byline) => byline,
+            };
+            if let Some(byline) = byline {
+                article_content = article_content.push(
+                    text(byline)
+                        .size(13)
+                        .style(|theme: &Theme| {
+                            text::Style {
+                                color: Some(theme.extended_palette().background.strong.text),
+                            }
+                        })
+                );
+            }
+
             // Image indicator if available and enabled
             if settings.show_images {
                 if let Some(image_url) = &article.image_url {
@@ -142,6 +338,19 @@ pub fn content_view<'a>(
                 }
             }
 
+            // Categories as tags
+            if !article.categories.is_empty() {
+                article_content = article_content.push(
+                    text(tags(&article.categories))
+                        .size(12)
+                        .style(|theme: &Theme| {
+                            text::Style {
+                                color: Some(theme.extended_palette().secondary.base.color),
+                            }
+                        })
+                );
+            }
+
             // Link with accent color
             article_content = article_content.push(
                 text(&article.link)
@@ -181,7 +390,7 @@ pub fn content_view<'a>(
                 });
 
             let article_button = button(article_card)
-                .on_press(ContentMessage::ArticleClicked(article.clone()))
+                .on_press(ContentMessage::ArticleClicked(Box::new(article.clone())))
                 .padding(0)
                 .style(|theme: &Theme, status| {
                     let base = button::Style {
@@ -204,7 +413,25 @@ pub fn content_view<'a>(
                     }
                 });
 
-            article_list = article_list.push(article_button);
+            let is_selected = selected.contains(&key);
+            let (feed_url, entry_id) = key;
+            article_list = article_list.push(
+                row![
+                    checkbox("", is_selected)
+                        .on_toggle(move |_| ContentMessage::ToggleSelected(feed_url.clone(), entry_id.clone())),
+                    article_button.width(Length::Fill),
+                    header_button(
+                        if is_starred { "★" } else { "☆" },
+                        ContentMessage::ToggleStar(Box::new(article.clone())),
+                    ),
+                    header_button(
+                        if is_viewed { "○ Unread" } else { "● Read" },
+                        ContentMessage::ToggleRead(article.feed_url.clone(), article.id.clone()),
+                    ),
+                ]
+                .spacing(12)
+                .align_y(iced::Alignment::Center)
+            );
         }
     }
 
@@ -224,6 +451,97 @@ pub fn content_view<'a>(
         .into()
 }
 
+/// Card for a search hit: title, source and the snippet with matches highlighted
+fn search_result_card<'a>(result: &'a SearchResult, feeds: &'a [Feed]) -> Element<'a, ContentMessage> {
+    let article = &result.article;
+    let source = feeds
+        .iter()
+        .find(|f| f.url == article.feed_url)
+        .map_or(article.feed_url.as_str(), |f| f.title.as_str());
+    let snippet: Vec<_> = result
+        .snippet
+        .iter()
+        .map(|part| {
+            let run = span(part.text.as_str());
+            if part.highlighted {
+                run.background(iced::Color::from_rgba(1.0, 0.8, 0.2, 0.45))
+            } else {
+                run
+            }
+        })
+        .collect();
+
+    let card = container(
+        column![
+            text(&article.title).size(18),
+            text(source)
+                .size(13)
+                .style(|theme: &Theme| {
+                    text::Style {
+                        color: Some(theme.extended_palette().background.strong.text),
+                    }
+                }),
+            rich_text(snippet).size(14),
+        ]
+        .spacing(8)
+        .padding(Padding::from([16, 20]))
+    )
+    .width(Length::Fill)
+    .style(|theme: &Theme| {
+        container::Style {
+            background: Some(iced::Background::Color(theme.extended_palette().background.weak.color)),
+            border: iced::Border {
+                radius: 12.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    });
+
+    button(card)
+        .on_press(ContentMessage::ArticleClicked(Box::new(article.clone())))
+        .padding(0)
+        .style(|theme: &Theme, _status| {
+            button::Style {
+                background: None,
+                text_color: theme.palette().text,
+                ..Default::default()
+            }
+        })
+        .into()
+}
+
+fn header_button<'a>(label: impl text::IntoFragment<'a>, message: ContentMessage) -> Element<'a, ContentMessage> {
+    button(text(label).size(14))
+        .on_press(message)
+        .padding([8, 16])
+        .style(|theme: &Theme, status| {
+            let palette = theme.extended_palette();
+            let base = button::Style {
+                background: Some(iced::Background::Color(palette.background.weak.color)),
+                text_color: palette.background.base.text,
+                border: iced::Border {
+                    radius: 8.0.into(),
+                    ..Default::default()
+                },
+                shadow: Shadow::default(),
+            };
+
+            match status {
+                button::Status::Hovered => button::Style {
+                    background: Some(iced::Background::Color(palette.background.strong.color)),
+                    ..base
+                },
+                button::Status::Pressed => button::Style {
+                    background: Some(iced::Background::Color(palette.primary.weak.color)),
+                    ..base
+                },
+                _ => base,
+            }
+        })
+        .into()
+}
+
 fn truncate_text(text: &str, max_len: usize) -> String {
     if text.len() <= max_len {
         text.to_string()
@@ -231,3 +549,46 @@ fn truncate_text(text: &str, max_len: usize) -> String {
         format!("{}...", &text[..max_len])
     }
 }
+
+/// "Author · 3h ago" line for an article, if it has either
+pub(crate) fn byline(article: &Article, now: chrono::DateTime<chrono::Utc>) -> Option<String> {
+    let mut parts = Vec::new();
+    if !article.authors.is_empty() {
+        parts.push(article.authors.join(", "));
+    }
+    if let Some(date) = article.date() {
+        parts.push(format_relative(date, now));
+    }
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(" · "))
+    }
+}
+
+/// Render categories as "#tag #tag"
+pub(crate) fn tags(categories: &[String]) -> String {
+    categories
+        .iter()
+        .map(|c| format!("#{}", c))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Choice in the search age picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct MaxAge(Option<u32>);
+
+impl MaxAge {
+    const ALL: [MaxAge; 4] = [MaxAge(None), MaxAge(Some(1)), MaxAge(Some(7)), MaxAge(Some(30))];
+}
+
+impl std::fmt::Display for MaxAge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            None => write!(f, "Any time"),
+            Some(1) => write!(f, "Last day"),
+            Some(days) => write!(f, "Last {} days", days),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use iced::widget::{button, column, container, scrollable, text, Column};
+use iced::{Element, Length, Padding, Shadow, Theme};
+use crate::models::FeedCandidate;
+
+#[derive(Debug, Clone)]
+pub enum FeedChooserMessage {
+    Choose(String),
+    Cancel,
+}
+
+/// Lets the user pick which of the feeds linked from a web page to subscribe to
+pub fn feed_chooser_view<'a>(page_url: &'a str, candidates: &'a [FeedCandidate]) -> Element<'a, FeedChooserMessage> {
+    let mut content = Column::new()
+        .spacing(24)
+        .padding(Padding::from([40, 50]))
+        .max_width(700);
+
+    content = content.push(
+        text("Choose a Feed")
+            .size(40)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.palette().text),
+                }
+            })
+    );
+
+    // Material divider
+    content = content.push(
+        container(column![])
+            .height(2)
+            .width(Length::Fill)
+            .style(|theme: &Theme| {
+                container::Style {
+                    background: Some(iced::Background::Color(theme.extended_palette().primary.strong.color)),
+                    border: iced::Border {
+                        radius: 1.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+            })
+    );
+
+    content = content.push(
+        text(format!("{} offers {} feeds. Pick one to subscribe to.", page_url, candidates.len()))
+            .size(15)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.extended_palette().background.strong.text),
+                }
+            })
+    );
+
+    for candidate in candidates {
+        content = content.push(candidate_button(candidate));
+    }
+
+    content = content.push(
+        button(text("Cancel").size(16))
+            .on_press(FeedChooserMessage::Cancel)
+            .padding([14, 32])
+            .width(Length::Fill)
+            .style(|theme: &Theme, status| {
+                let palette = theme.extended_palette();
+                let base = button::Style {
+                    background: Some(iced::Background::Color(palette.background.strong.color)),
+                    text_color: palette.background.strong.text,
+                    border: iced::Border {
+                        radius: 8.0.into(),
+                        ..Default::default()
+                    },
+                    shadow: Shadow::default(),
+                };
+
+                match status {
+                    button::Status::Hovered | button::Status::Pressed => button::Style {
+                        background: Some(iced::Background::Color(palette.background.weak.color)),
+                        ..base
+                    },
+                    _ => base,
+                }
+            })
+    );
+
+    container(scrollable(content).height(Length::Fill))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .style(|theme: &Theme| {
+            container::Style {
+                background: Some(iced::Background::Color(theme.extended_palette().background.base.color)),
+                ..Default::default()
+            }
+        })
+        .into()
+}
+
+fn candidate_button(candidate: &FeedCandidate) -> Element<'_, FeedChooserMessage> {
+    let title = candidate.title.as_deref().unwrap_or(&candidate.url);
+
+    button(
+        column![
+            text(title)
+                .size(18)
+                .style(|theme: &Theme| {
+                    text::Style {
+                        color: Some(theme.palette().text),
+                    }
+                }),
+            text(&candidate.url)
+                .size(12)
+                .style(|theme: &Theme| {
+                    text::Style {
+                        color: Some(theme.extended_palette().background.strong.text),
+                    }
+                }),
+        ]
+        .spacing(6)
+    )
+    .on_press(FeedChooserMessage::Choose(candidate.url.clone()))
+    .padding(Padding::from([20, 24]))
+    .width(Length::Fill)
+    .style(|theme: &Theme, status| {
+        let palette = theme.extended_palette();
+        let base = button::Style {
+            background: Some(iced::Background::Color(palette.background.weak.color)),
+            text_color: palette.background.weak.text,
+            border: iced::Border {
+                radius: 12.0.into(),
+                ..Default::default()
+            },
+            shadow: Shadow {
+                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+                offset: iced::Vector::new(0.0, 2.0),
+                blur_radius: 8.0,
+            },
+        };
+
+        match status {
+            button::Status::Hovered => button::Style {
+                shadow: Shadow {
+                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.2),
+                    offset: iced::Vector::new(0.0, 4.0),
+                    blur_radius: 12.0,
+                },
+                ..base
+            },
+            _ => base,
+        }
+    })
+    .into()
+}
@@ -0,0 +1,223 @@
+use chrono::Utc;
+use iced::widget::{button, column, container, row, scrollable, text, Column};
+use iced::{Element, Length, Padding, Shadow, Theme};
+use crate::models::Feed;
+use crate::utils::format_relative;
+
+#[derive(Debug, Clone)]
+pub enum FeedHealthMessage {
+    RemoveFeed(String),
+    Close,
+}
+
+/// Lists feeds that are gone or failing to refresh, so they can be pruned
+pub fn feed_health_view(feeds: &[Feed]) -> Element<'_, FeedHealthMessage> {
+    let now = Utc::now();
+    let broken: Vec<&Feed> = feeds
+        .iter()
+        .filter(|f| f.dead || f.health.is_failing())
+        .collect();
+
+    let mut content = Column::new()
+        .spacing(24)
+        .padding(Padding::from([40, 50]))
+        .max_width(700);
+
+    content = content.push(
+        text("Feed Health")
+            .size(40)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.palette().text),
+                }
+            })
+    );
+
+    // Material divider
+    content = content.push(
+        container(column![])
+            .height(2)
+            .width(Length::Fill)
+            .style(|theme: &Theme| {
+                container::Style {
+                    background: Some(iced::Background::Color(theme.extended_palette().primary.strong.color)),
+                    border: iced::Border {
+                        radius: 1.0.into(),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }
+            })
+    );
+
+    let summary = if broken.is_empty() {
+        "All feeds are refreshing normally.".to_string()
+    } else {
+        format!("{} of {} feeds need attention.", broken.len(), feeds.len())
+    };
+    content = content.push(
+        text(summary)
+            .size(15)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.extended_palette().background.strong.text),
+                }
+            })
+    );
+
+    for feed in broken {
+        content = content.push(feed_card(feed, now));
+    }
+
+    // Close button with Material FAB style
+    content = content.push(
+        button(text("Close").size(16))
+            .on_press(FeedHealthMessage::Close)
+            .padding([14, 32])
+            .width(Length::Fill)
+            .style(|theme: &Theme, status| {
+                let palette = theme.extended_palette();
+                let base = button::Style {
+                    background: Some(iced::Background::Color(palette.primary.strong.color)),
+                    text_color: palette.primary.strong.text,
+                    border: iced::Border {
+                        radius: 8.0.into(),
+                        ..Default::default()
+                    },
+                    shadow: Shadow {
+                        color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.15),
+                        offset: iced::Vector::new(0.0, 2.0),
+                        blur_radius: 4.0,
+                    },
+                };
+
+                match status {
+                    button::Status::Hovered => button::Style {
+                        shadow: Shadow {
+                            color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.25),
+                            offset: iced::Vector::new(0.0, 4.0),
+                            blur_radius: 8.0,
+                        },
+                        ..base
+                    },
+                    button::Status::Pressed => button::Style {
+                        background: Some(iced::Background::Color(palette.primary.base.color)),
+                        ..base
+                    },
+                    _ => base,
+                }
+            })
+    );
+
+    container(scrollable(content).height(Length::Fill))
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .style(|theme: &Theme| {
+            container::Style {
+                background: Some(iced::Background::Color(theme.extended_palette().background.base.color)),
+                ..Default::default()
+            }
+        })
+        .into()
+}
+
+fn feed_card(feed: &Feed, now: chrono::DateTime<Utc>) -> Element<'_, FeedHealthMessage> {
+    let health = &feed.health;
+    let state = if feed.dead {
+        "⛔ Removed by its publisher (HTTP 410)".to_string()
+    } else {
+        format!(
+            "⚠ {} failed refresh{} in a row",
+            health.consecutive_failures,
+            if health.consecutive_failures == 1 { "" } else { "es" }
+        )
+    };
+    let last_error = match (&health.last_error, health.last_error_at) {
+        (Some(error), Some(at)) => format!("Last error {}: {}", format_relative(at, now), error),
+        (Some(error), None) => format!("Last error: {}", error),
+        _ => "No errors recorded".to_string(),
+    };
+    let last_success = match health.last_success_at {
+        Some(at) => format!("Last successful refresh {}", format_relative(at, now)),
+        None => "Never refreshed successfully".to_string(),
+    };
+    let latency = health
+        .avg_latency_ms
+        .map(|ms| format!(" · responds in {:.0} ms on average", ms))
+        .unwrap_or_default();
+
+    let secondary = |theme: &Theme| text::Style {
+        color: Some(theme.extended_palette().background.strong.text),
+    };
+
+    container(
+        row![
+            column![
+                text(&feed.title)
+                    .size(18)
+                    .style(|theme: &Theme| {
+                        text::Style {
+                            color: Some(theme.palette().text),
+                        }
+                    }),
+                text(&feed.url).size(12).style(secondary),
+                text(state)
+                    .size(14)
+                    .style(|theme: &Theme| {
+                        text::Style {
+                            color: Some(theme.extended_palette().danger.base.color),
+                        }
+                    }),
+                text(last_error).size(13).style(secondary),
+                text(format!("{}{}", last_success, latency)).size(13).style(secondary),
+            ]
+            .spacing(6)
+            .width(Length::Fill),
+            button(text("Remove").size(14))
+                .on_press(FeedHealthMessage::RemoveFeed(feed.url.clone()))
+                .padding([8, 16])
+                .style(|theme: &Theme, status| {
+                    let palette = theme.extended_palette();
+                    let base = button::Style {
+                        background: Some(iced::Background::Color(palette.danger.base.color)),
+                        text_color: palette.danger.base.text,
+                        border: iced::Border {
+                            radius: 8.0.into(),
+                            ..Default::default()
+                        },
+                        shadow: Shadow::default(),
+                    };
+
+                    match status {
+                        button::Status::Hovered | button::Status::Pressed => button::Style {
+                            background: Some(iced::Background::Color(palette.danger.strong.color)),
+                            ..base
+                        },
+                        _ => base,
+                    }
+                }),
+        ]
+        .spacing(16)
+        .align_y(iced::Alignment::Center)
+    )
+    .width(Length::Fill)
+    .padding(Padding::from([20, 24]))
+    .style(|theme: &Theme| {
+        let palette = theme.extended_palette();
+        container::Style {
+            background: Some(iced::Background::Color(palette.background.weak.color)),
+            border: iced::Border {
+                radius: 12.0.into(),
+                ..Default::default()
+            },
+            shadow: Shadow {
+                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+                offset: iced::Vector::new(0.0, 2.0),
+                blur_radius: 8.0,
+            },
+            ..Default::default()
+        }
+    })
+    .into()
+}
@@ -2,8 +2,12 @@ pub mod sidebar;
 pub mod content;
 pub mod settings;
 pub mod article_detail;
+pub mod feed_health;
+pub mod feed_chooser;
 
 pub use sidebar::sidebar_view;
 pub use content::content_view;
 pub use settings::settings_view;
 pub use article_detail::article_detail_view;
+pub use feed_health::feed_health_view;
+pub use feed_chooser::feed_chooser_view;
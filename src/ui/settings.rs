@@ -1,16 +1,37 @@
-use iced::widget::{button, column, container, pick_list, text, Column};
+use iced::widget::{button, column, container, pick_list, row, scrollable, text, text_input, Column};
 use iced::{Element, Length, Padding, Shadow, Theme};
-use crate::models::{AppSettings, AppTheme};
+use crate::models::{AppSettings, AppTheme, ImportPlan, StorageStats};
 
 #[derive(Debug, Clone)]
 pub enum SettingsMessage {
     ThemeSelected(AppTheme),
     ToggleImages,
     ToggleExcerpts,
+    RefreshIntervalSelected(u64),
+    TogglePauseRefresh,
+    /// Purge articles older than this many days; 0 keeps them
+    RetentionAgeSelected(u32),
+    /// Keep this many articles per feed; 0 keeps them all
+    RetentionCountSelected(u32),
+    /// Purge old articles and compact the database right away
+    CleanUpNow,
+    OpmlPathChanged(String),
+    PreviewImport,
+    ConfirmImport,
+    CancelImport,
+    ExportOpml,
     CloseSettings,
 }
 
-pub fn settings_view<'a>(settings: &'a AppSettings) -> Element<'a, SettingsMessage> {
+/// `opml_status` reports the outcome of the last OPML import or export, and
+/// `import_plan` is the dry run of an import waiting to be confirmed
+pub fn settings_view<'a>(
+    settings: &'a AppSettings,
+    storage: Option<&'a StorageStats>,
+    opml_path: &'a str,
+    import_plan: Option<&'a ImportPlan>,
+    opml_status: Option<&'a str>,
+) -> Element<'a, SettingsMessage> {
     let mut content = Column::new()
         .spacing(24)
         .padding(Padding::from([40, 50]))
@@ -169,6 +190,215 @@ pub fn settings_view<'a>(settings: &'a AppSettings) -> Element<'a, SettingsMessa
         })
     );
 
+    // Background refresh section
+    content = content.push(
+        container(
+            column![
+                text("Background Refresh")
+                    .size(20)
+                    .style(|theme: &Theme| {
+                        text::Style {
+                            color: Some(theme.palette().text),
+                        }
+                    }),
+                text("How often feeds are refreshed automatically")
+                    .size(14)
+                    .style(|theme: &Theme| {
+                        text::Style {
+                            color: Some(theme.extended_palette().background.strong.text),
+                        }
+                    }),
+                pick_list(
+                    RefreshInterval::ALL,
+                    Some(RefreshInterval(settings.refresh_interval_minutes)),
+                    |interval| SettingsMessage::RefreshIntervalSelected(interval.0),
+                )
+                .text_size(15)
+                .padding(12)
+                .width(Length::Fill)
+                .style(|theme: &Theme, status| {
+                    let palette = theme.extended_palette();
+                    pick_list::Style {
+                        text_color: palette.background.base.text,
+                        background: iced::Background::Color(palette.background.weak.color),
+                        placeholder_color: palette.background.strong.text,
+                        handle_color: palette.secondary.base.color,
+                        border: iced::Border {
+                            color: if matches!(status, pick_list::Status::Active) {
+                                palette.primary.strong.color
+                            } else {
+                                palette.background.strong.color
+                            },
+                            width: if matches!(status, pick_list::Status::Active) { 2.0 } else { 1.0 },
+                            radius: 8.0.into(),
+                        },
+                    }
+                }),
+                create_toggle_button(
+                    if settings.refresh_paused { "✓ Pause Automatic Refresh" } else { "Pause Automatic Refresh" },
+                    settings.refresh_paused,
+                    SettingsMessage::TogglePauseRefresh
+                ),
+            ]
+            .spacing(12)
+            .padding(Padding::from([20, 24]))
+        )
+        .width(Length::Fill)
+        .style(|theme: &Theme| {
+            let palette = theme.extended_palette();
+            container::Style {
+                background: Some(iced::Background::Color(palette.background.weak.color)),
+                border: iced::Border {
+                    color: palette.background.strong.color,
+                    width: 0.0,
+                    radius: 12.0.into(),
+                },
+                shadow: Shadow {
+                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+                    offset: iced::Vector::new(0.0, 2.0),
+                    blur_radius: 8.0,
+                },
+                ..Default::default()
+            }
+        })
+    );
+
+    // Storage section: how long articles are kept and how much space they take
+    let mut storage_section = column![
+        text("Storage")
+            .size(20)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.palette().text),
+                }
+            }),
+        text("Articles past these limits are purged every few hours; starred articles are always kept")
+            .size(14)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.extended_palette().background.strong.text),
+                }
+            }),
+        row![
+            pick_list(
+                RetentionAge::ALL,
+                Some(RetentionAge(settings.retention.max_age_days)),
+                |age| SettingsMessage::RetentionAgeSelected(age.0),
+            )
+            .text_size(15)
+            .padding(12)
+            .width(Length::Fill)
+            .style(picker_style),
+            pick_list(
+                RetentionCount::ALL,
+                Some(RetentionCount(settings.retention.max_articles_per_feed)),
+                |count| SettingsMessage::RetentionCountSelected(count.0),
+            )
+            .text_size(15)
+            .padding(12)
+            .width(Length::Fill)
+            .style(picker_style),
+        ]
+        .spacing(12),
+    ]
+    .spacing(12)
+    .padding(Padding::from([20, 24]));
+
+    if let Some(stats) = storage {
+        storage_section = storage_section.push(
+            text(format!(
+                "Database: {} ({} reclaimable) · {} articles · {} starred · {} marked read",
+                format_bytes(stats.database_bytes),
+                format_bytes(stats.free_bytes),
+                stats.articles,
+                stats.starred,
+                stats.read_markers,
+            ))
+            .size(14)
+        );
+    }
+    storage_section = storage_section.push(create_toggle_button("Clean Up Now", false, SettingsMessage::CleanUpNow));
+
+    content = content.push(
+        container(storage_section)
+        .width(Length::Fill)
+        .style(|theme: &Theme| {
+            let palette = theme.extended_palette();
+            container::Style {
+                background: Some(iced::Background::Color(palette.background.weak.color)),
+                border: iced::Border {
+                    color: palette.background.strong.color,
+                    width: 0.0,
+                    radius: 12.0.into(),
+                },
+                shadow: Shadow {
+                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+                    offset: iced::Vector::new(0.0, 2.0),
+                    blur_radius: 8.0,
+                },
+                ..Default::default()
+            }
+        })
+    );
+
+    // Subscriptions import/export section
+    let mut subscriptions = column![
+        text("Subscriptions")
+            .size(20)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.palette().text),
+                }
+            }),
+        text("Import or export your feeds as an OPML file")
+            .size(14)
+            .style(|theme: &Theme| {
+                text::Style {
+                    color: Some(theme.extended_palette().background.strong.text),
+                }
+            }),
+        text_input("Path to OPML file...", opml_path)
+            .on_input(SettingsMessage::OpmlPathChanged)
+            .padding(12)
+            .size(15),
+        row![
+            create_toggle_button("Preview Import", false, SettingsMessage::PreviewImport),
+            create_toggle_button("Export", false, SettingsMessage::ExportOpml),
+        ]
+        .spacing(12),
+    ]
+    .spacing(12)
+    .padding(Padding::from([20, 24]));
+
+    if let Some(status) = opml_status {
+        subscriptions = subscriptions.push(text(status).size(14));
+    }
+    if let Some(plan) = import_plan {
+        subscriptions = subscriptions.push(import_report(plan));
+    }
+
+    content = content.push(
+        container(subscriptions)
+        .width(Length::Fill)
+        .style(|theme: &Theme| {
+            let palette = theme.extended_palette();
+            container::Style {
+                background: Some(iced::Background::Color(palette.background.weak.color)),
+                border: iced::Border {
+                    color: palette.background.strong.color,
+                    width: 0.0,
+                    radius: 12.0.into(),
+                },
+                shadow: Shadow {
+                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+                    offset: iced::Vector::new(0.0, 2.0),
+                    blur_radius: 8.0,
+                },
+                ..Default::default()
+            }
+        })
+    );
+
     content = content.push(
         container(column![])
             .height(1)
@@ -226,10 +456,10 @@ pub fn settings_view<'a>(settings: &'a AppSettings) -> Element<'a, SettingsMessa
             })
     );
 
-    container(content)
+    container(scrollable(content).height(Length::Fill))
         .width(Length::Fill)
         .height(Length::Fill)
-        .center(Length::Fill)
+        .center_x(Length::Fill)
         .style(|theme: &Theme| {
             container::Style {
                 background: Some(iced::Background::Color(theme.extended_palette().background.base.color)),
@@ -239,6 +469,58 @@ pub fn settings_view<'a>(settings: &'a AppSettings) -> Element<'a, SettingsMessa
         .into()
 }
 
+/// Dry run of an OPML import: what will be added and what will be skipped
+fn import_report(plan: &ImportPlan) -> Element<'_, SettingsMessage> {
+    let secondary = |theme: &Theme| text::Style {
+        color: Some(theme.extended_palette().background.strong.text),
+    };
+
+    let mut report = Column::new().spacing(6);
+    report = report.push(
+        text(format!(
+            "{} new, {} already subscribed, {} invalid",
+            plan.new_feeds.len(),
+            plan.duplicates.len(),
+            plan.invalid.len()
+        ))
+        .size(15),
+    );
+    for feed in &plan.new_feeds {
+        let title = feed.title.as_deref().unwrap_or(&feed.url);
+        let folder = if feed.folders.is_empty() {
+            String::new()
+        } else {
+            format!("{} / ", feed.folders.join(" / "))
+        };
+        report = report.push(text(format!("+ {}{}", folder, title)).size(13));
+    }
+    for feed in &plan.duplicates {
+        let title = feed.title.as_deref().unwrap_or(&feed.url);
+        report = report.push(text(format!("= {} (already subscribed)", title)).size(13).style(secondary));
+    }
+    for outline in &plan.invalid {
+        report = report.push(
+            text(format!("✕ {}: {}", outline.label, outline.reason))
+                .size(13)
+                .style(|theme: &Theme| {
+                    text::Style {
+                        color: Some(theme.extended_palette().danger.base.color),
+                    }
+                }),
+        );
+    }
+
+    let actions = if plan.new_feeds.is_empty() {
+        row![create_toggle_button("Close Report", false, SettingsMessage::CancelImport)]
+    } else {
+        row![
+            create_toggle_button("Import Feeds", true, SettingsMessage::ConfirmImport),
+            create_toggle_button("Cancel", false, SettingsMessage::CancelImport),
+        ]
+    };
+    column![report, actions.spacing(12)].spacing(12).into()
+}
+
 fn create_toggle_button(label: &str, is_active: bool, message: SettingsMessage) -> Element<'_, SettingsMessage> {
     button(text(label).size(15))
         .on_press(message)
@@ -299,3 +581,108 @@ fn create_toggle_button(label: &str, is_active: bool, message: SettingsMessage)
         })
         .into()
 }
+
+/// Choice in the refresh interval picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RefreshInterval(pub(crate) u64);
+
+impl RefreshInterval {
+    pub(crate) const ALL: [RefreshInterval; 6] = [
+        RefreshInterval(5),
+        RefreshInterval(15),
+        RefreshInterval(30),
+        RefreshInterval(60),
+        RefreshInterval(180),
+        RefreshInterval(720),
+    ];
+}
+
+impl std::fmt::Display for RefreshInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            minutes if minutes % 60 == 0 && minutes > 60 => write!(f, "Every {} hours", minutes / 60),
+            60 => write!(f, "Every hour"),
+            minutes => write!(f, "Every {} minutes", minutes),
+        }
+    }
+}
+
+/// Choice in the article age retention picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RetentionAge(u32);
+
+impl RetentionAge {
+    const ALL: [RetentionAge; 5] = [
+        RetentionAge(30),
+        RetentionAge(90),
+        RetentionAge(180),
+        RetentionAge(365),
+        RetentionAge(0),
+    ];
+}
+
+impl std::fmt::Display for RetentionAge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "Keep articles forever"),
+            days => write!(f, "Keep articles {} days", days),
+        }
+    }
+}
+
+/// Choice in the articles-per-feed retention picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RetentionCount(u32);
+
+impl RetentionCount {
+    const ALL: [RetentionCount; 5] = [
+        RetentionCount(100),
+        RetentionCount(500),
+        RetentionCount(1000),
+        RetentionCount(5000),
+        RetentionCount(0),
+    ];
+}
+
+impl std::fmt::Display for RetentionCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            0 => write!(f, "No limit per feed"),
+            count => write!(f, "At most {} per feed", count),
+        }
+    }
+}
+
+fn picker_style(theme: &Theme, status: pick_list::Status) -> pick_list::Style {
+    let palette = theme.extended_palette();
+    pick_list::Style {
+        text_color: palette.background.base.text,
+        background: iced::Background::Color(palette.background.weak.color),
+        placeholder_color: palette.background.strong.text,
+        handle_color: palette.secondary.base.color,
+        border: iced::Border {
+            color: if matches!(status, pick_list::Status::Active) {
+                palette.primary.strong.color
+            } else {
+                palette.background.strong.color
+            },
+            width: if matches!(status, pick_list::Status::Active) { 2.0 } else { 1.0 },
+            radius: 8.0.into(),
+        },
+    }
+}
+
+/// Size in bytes as a short human-readable string, e.g. "12.3 MB"
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
@@ -1,18 +1,73 @@
-use iced::widget::{button, column, container, text, text_input, Column};
+use iced::widget::{button, column, container, pick_list, row, scrollable, text, text_input, Column};
 use iced::{Element, Length, Padding, Shadow, Theme};
-use crate::models::Feed;
+use std::collections::HashMap;
+use crate::models::{Feed, FeedStatus, Folder, SavedSearch};
+use super::settings::RefreshInterval;
 
 #[derive(Debug, Clone)]
 pub enum SidebarMessage {
     FeedInputChanged(String),
     AddFeed,
-    RefreshFeed(String),
+    ShowAllArticles,
+    ShowStarred,
+    RefreshAll,
+    SelectFeed(String),
+    RemoveFeed(String),
+    MoveFeedUp(String),
+    MoveFeedDown(String),
+    SelectFolder(i64),
+    ToggleFolder(i64),
+    DeleteFolder(i64),
+    FolderInputChanged(String),
+    /// Create a folder named after the folder input, inside the given parent
+    CreateFolder(Option<i64>),
+    /// Rename a folder to the folder input
+    RenameFolder(i64),
+    MoveFeedToFolder(String, Option<i64>),
+    /// Refresh a feed this many minutes apart, or as often as the others
+    SetFeedRefreshInterval(String, Option<u64>),
+    SelectSavedSearch(i64),
+    DeleteSavedSearch(i64),
+    OpenFeedHealth,
     OpenSettings,
 }
 
+/// What the article list shows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Selection {
+    /// The merged timeline of all feeds
+    #[default]
+    AllArticles,
+    Feed(String),
+    /// The merged timeline of a folder's feeds, including its subfolders
+    Folder(i64),
+    /// Snapshots of starred articles, whether or not their feed still has them
+    Starred,
+    /// The articles currently matching a saved search
+    SavedSearch(i64),
+}
+
+impl Selection {
+    /// URL of the selected feed, if a single feed is selected
+    pub fn feed(&self) -> Option<&str> {
+        match self {
+            Selection::Feed(url) => Some(url),
+            _ => None,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn sidebar_view<'a>(
     feeds: &'a [Feed],
+    folders: &'a [Folder],
+    saved_searches: &'a [SavedSearch],
+    feed_status: &'a HashMap<String, FeedStatus>,
+    unread_counts: &'a HashMap<String, usize>,
+    saved_search_unread: &'a HashMap<i64, usize>,
+    selection: &'a Selection,
     feed_input: &'a str,
+    folder_input: &'a str,
 ) -> Element<'a, SidebarMessage> {
     let mut feed_list = Column::new()
         .spacing(12)
@@ -42,49 +97,187 @@ pub fn sidebar_view<'a>(
             })
     );
 
-    // Feed list with Material card style and hover effects
-    for feed in feeds {
+    // River view merging every feed
+    feed_list = feed_list.push(
+        row![
+            feed_button(
+                with_unread("📰 All Articles".to_string(), unread_counts.values().sum()),
+                *selection == Selection::AllArticles,
+                SidebarMessage::ShowAllArticles,
+            ),
+            feed_action_button("⟳", SidebarMessage::RefreshAll),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center)
+    );
+    feed_list = feed_list.push(feed_button("⭐ Starred", *selection == Selection::Starred, SidebarMessage::ShowStarred));
+
+    // Smart feeds re-run their saved search whenever they're shown
+    for search in saved_searches {
+        let label = with_unread(
+            format!("🔎 {}", search.name),
+            saved_search_unread.get(&search.id).copied().unwrap_or(0),
+        );
         feed_list = feed_list.push(
-            button(
-                text(&feed.title)
-                    .size(15)
-            )
-            .on_press(SidebarMessage::RefreshFeed(feed.url.clone()))
-            .padding([12, 16])
+            row![
+                feed_button(
+                    label,
+                    *selection == Selection::SavedSearch(search.id),
+                    SidebarMessage::SelectSavedSearch(search.id),
+                ),
+                feed_action_button("✕", SidebarMessage::DeleteSavedSearch(search.id)),
+            ]
+            .spacing(4)
+            .align_y(iced::Alignment::Center)
+        );
+    }
+
+    // Folders, each followed by its subfolders and feeds, then unfiled feeds
+    let feed_row = |feed: &'a Feed, depth: u16| {
+        let label = match feed_status.get(&feed.url) {
+            Some(FeedStatus::Pending) => format!("⏳ {}", feed.title),
+            Some(FeedStatus::Error(_)) => format!("⚠ {}", feed.title),
+            _ if feed.dead => format!("⛔ {}", feed.title),
+            _ => feed.title.clone(),
+        };
+        let label = with_unread(label, unread_counts.get(&feed.url).copied().unwrap_or(0));
+        let is_selected = selection.feed() == Some(feed.url.as_str());
+        indented(
+            row![
+                feed_button(label, is_selected, SidebarMessage::SelectFeed(feed.url.clone())),
+                feed_action_button("↑", SidebarMessage::MoveFeedUp(feed.url.clone())),
+                feed_action_button("↓", SidebarMessage::MoveFeedDown(feed.url.clone())),
+                feed_action_button("✕", SidebarMessage::RemoveFeed(feed.url.clone())),
+            ]
+            .spacing(4)
+            .align_y(iced::Alignment::Center)
+            .into(),
+            depth,
+        )
+    };
+    let folder_row = |folder: &'a Folder, depth: u16| {
+        let member_ids = folder_and_subfolders(folders, folder.id);
+        let unread: usize = feeds
+            .iter()
+            .filter(|f| f.folder_id.is_some_and(|id| member_ids.contains(&id)))
+            .filter_map(|f| unread_counts.get(&f.url))
+            .sum();
+        let label = with_unread(format!("📁 {}", folder.name), unread);
+        indented(
+            row![
+                feed_action_button(if folder.collapsed { "▸" } else { "▾" }, SidebarMessage::ToggleFolder(folder.id)),
+                feed_button(label, *selection == Selection::Folder(folder.id), SidebarMessage::SelectFolder(folder.id)),
+                feed_action_button("✕", SidebarMessage::DeleteFolder(folder.id)),
+            ]
+            .spacing(4)
+            .align_y(iced::Alignment::Center)
+            .into(),
+            depth,
+        )
+    };
+
+    let mut tree = Column::new().spacing(12);
+    for folder in folders.iter().filter(|f| f.parent_id.is_none()) {
+        tree = tree.push(folder_row(folder, 0));
+        if folder.collapsed {
+            continue;
+        }
+        for subfolder in folders.iter().filter(|f| f.parent_id == Some(folder.id)) {
+            tree = tree.push(folder_row(subfolder, 1));
+            if !subfolder.collapsed {
+                for feed in feeds.iter().filter(|f| f.folder_id == Some(subfolder.id)) {
+                    tree = tree.push(feed_row(feed, 2));
+                }
+            }
+        }
+        for feed in feeds.iter().filter(|f| f.folder_id == Some(folder.id)) {
+            tree = tree.push(feed_row(feed, 1));
+        }
+    }
+    // Feeds whose folder no longer exists are listed with the unfiled ones
+    for feed in feeds
+        .iter()
+        .filter(|f| f.folder_id.is_none_or(|id| folders.iter().all(|folder| folder.id != id)))
+    {
+        tree = tree.push(feed_row(feed, 0));
+    }
+    feed_list = feed_list.push(scrollable(tree).height(Length::Fill));
+
+    feed_list = feed_list.push(
+        container(column![])
+            .height(1)
             .width(Length::Fill)
-            .style(|theme: &Theme, status| {
-                let palette = theme.extended_palette();
-                let base = button::Style {
-                    background: Some(iced::Background::Color(palette.background.weak.color)),
-                    text_color: palette.background.weak.text,
-                    border: iced::Border {
-                        radius: 8.0.into(),
-                        ..Default::default()
-                    },
-                    shadow: Shadow {
-                        color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
-                        offset: iced::Vector::new(0.0, 2.0),
-                        blur_radius: 4.0,
-                    },
-                };
-                
-                match status {
-                    button::Status::Hovered => button::Style {
-                        background: Some(iced::Background::Color(palette.background.strong.color)),
-                        shadow: Shadow {
-                            color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.15),
-                            offset: iced::Vector::new(0.0, 4.0),
-                            blur_radius: 8.0,
-                        },
-                        ..base
-                    },
-                    button::Status::Pressed => button::Style {
-                        background: Some(iced::Background::Color(palette.primary.weak.color)),
-                        ..base
-                    },
-                    _ => base,
+            .style(|theme: &Theme| {
+                container::Style {
+                    background: Some(iced::Background::Color(theme.extended_palette().background.strong.color)),
+                    ..Default::default()
                 }
             })
+    );
+
+    // Folder tools act on the current selection
+    let selected_folder = match selection {
+        Selection::Folder(id) => folders.iter().find(|f| f.id == *id),
+        _ => None,
+    };
+    // Subfolders can only go inside top-level folders
+    let parent_id = selected_folder.filter(|f| f.parent_id.is_none()).map(|f| f.id);
+    feed_list = feed_list.push(
+        text_input("Folder name...", folder_input)
+            .on_input(SidebarMessage::FolderInputChanged)
+            .on_submit(SidebarMessage::CreateFolder(parent_id))
+            .padding(8)
+            .size(14)
+    );
+    let mut folder_tools = row![
+        footer_button(
+            if parent_id.is_some() { "New Subfolder" } else { "New Folder" },
+            SidebarMessage::CreateFolder(parent_id),
+        ),
+    ]
+    .spacing(8);
+    if let Some(folder) = selected_folder {
+        folder_tools = folder_tools.push(footer_button("Rename", SidebarMessage::RenameFolder(folder.id)));
+    }
+    feed_list = feed_list.push(folder_tools);
+
+    if let Some(feed) = selection.feed().and_then(|url| feeds.iter().find(|f| f.url == url)) {
+        let choices: Vec<FolderChoice> = std::iter::once(FolderChoice { id: None, name: "No folder".to_string() })
+            .chain(folders.iter().map(|f| FolderChoice {
+                id: Some(f.id),
+                name: match folders.iter().find(|parent| Some(parent.id) == f.parent_id) {
+                    Some(parent) => format!("{} / {}", parent.name, f.name),
+                    None => f.name.clone(),
+                },
+            }))
+            .collect();
+        let current = choices.iter().find(|c| c.id == feed.folder_id).cloned();
+        let url = feed.url.clone();
+        feed_list = feed_list.push(
+            row![
+                text("Move to").size(14),
+                pick_list(choices, current, move |choice: FolderChoice| {
+                    SidebarMessage::MoveFeedToFolder(url.clone(), choice.id)
+                })
+                .width(Length::Fill),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
+        );
+        let intervals: Vec<FeedRefresh> = std::iter::once(FeedRefresh(None))
+            .chain(RefreshInterval::ALL.iter().map(|interval| FeedRefresh(Some(interval.0))))
+            .collect();
+        let url = feed.url.clone();
+        feed_list = feed_list.push(
+            row![
+                text("Refresh").size(14),
+                pick_list(intervals, Some(FeedRefresh(feed.refresh_interval_minutes)), move |choice: FeedRefresh| {
+                    SidebarMessage::SetFeedRefreshInterval(url.clone(), choice.0)
+                })
+                .width(Length::Fill),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center)
         );
     }
 
@@ -113,7 +306,7 @@ pub fn sidebar_view<'a>(
 
     // Modern input field with theme colors
     feed_list = feed_list.push(
-        text_input("Enter feed URL...", feed_input)
+        text_input("Enter feed or website URL...", feed_input)
             .on_input(SidebarMessage::FeedInputChanged)
             .on_submit(SidebarMessage::AddFeed)
             .padding(12)
@@ -196,46 +389,17 @@ pub fn sidebar_view<'a>(
             })
     );
 
+    // Health of every feed, flagging how many are failing
+    let failing = feeds.iter().filter(|f| f.dead || f.health.is_failing()).count();
+    let health_label = if failing > 0 {
+        format!("🩺 Feed Health ({} failing)", failing)
+    } else {
+        "🩺 Feed Health".to_string()
+    };
+    feed_list = feed_list.push(footer_button(health_label, SidebarMessage::OpenFeedHealth));
+
     // Settings button with icon
-    feed_list = feed_list.push(
-        button(text("⚙ Settings").size(15))
-            .on_press(SidebarMessage::OpenSettings)
-            .padding([12, 24])
-            .width(Length::Fill)
-            .style(|theme: &Theme, status| {
-                let palette = theme.extended_palette();
-                let base = button::Style {
-                    background: Some(iced::Background::Color(palette.background.weak.color)),
-                    text_color: palette.background.base.text,
-                    border: iced::Border {
-                        radius: 8.0.into(),
-                        ..Default::default()
-                    },
-                    shadow: Shadow {
-                        color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
-                        offset: iced::Vector::new(0.0, 1.0),
-                        blur_radius: 2.0,
-                    },
-                };
-                
-                match status {
-                    button::Status::Hovered => button::Style {
-                        background: Some(iced::Background::Color(palette.background.strong.color)),
-                        shadow: Shadow {
-                            color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.15),
-                            offset: iced::Vector::new(0.0, 3.0),
-                            blur_radius: 6.0,
-                        },
-                        ..base
-                    },
-                    button::Status::Pressed => button::Style {
-                        background: Some(iced::Background::Color(palette.secondary.weak.color)),
-                        ..base
-                    },
-                    _ => base,
-                }
-            })
-    );
+    feed_list = feed_list.push(footer_button("⚙ Settings", SidebarMessage::OpenSettings));
 
     // Sidebar container with Material elevation
     container(feed_list)
@@ -260,3 +424,184 @@ pub fn sidebar_view<'a>(
         })
         .into()
 }
+
+/// IDs of a folder and its subfolders
+pub fn folder_and_subfolders(folders: &[Folder], folder_id: i64) -> Vec<i64> {
+    std::iter::once(folder_id)
+        .chain(folders.iter().filter(|f| f.parent_id == Some(folder_id)).map(|f| f.id))
+        .collect()
+}
+
+/// A destination in the move-to-folder picker
+#[derive(Debug, Clone, PartialEq)]
+struct FolderChoice {
+    id: Option<i64>,
+    name: String,
+}
+
+impl std::fmt::Display for FolderChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// Choice in a feed's refresh interval picker; `None` follows the global setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FeedRefresh(Option<u64>);
+
+impl std::fmt::Display for FeedRefresh {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(minutes) => RefreshInterval(minutes).fmt(f),
+            None => f.write_str("Same as other feeds"),
+        }
+    }
+}
+
+/// Append an unread badge to a sidebar label
+fn with_unread(label: String, unread: usize) -> String {
+    if unread > 0 {
+        format!("{} ({})", label, unread)
+    } else {
+        label
+    }
+}
+
+/// Indent a sidebar row by its nesting depth
+fn indented(content: Element<'_, SidebarMessage>, depth: u16) -> Element<'_, SidebarMessage> {
+    container(content)
+        .padding(Padding::ZERO.left(f32::from(depth * 16)))
+        .into()
+}
+
+fn feed_button<'a>(
+    label: impl text::IntoFragment<'a>,
+    is_selected: bool,
+    message: SidebarMessage,
+) -> Element<'a, SidebarMessage> {
+    button(
+        text(label)
+            .size(15)
+    )
+    .on_press(message)
+    .padding([12, 16])
+    .width(Length::Fill)
+    .style(move |theme: &Theme, status| {
+        let palette = theme.extended_palette();
+        let base = button::Style {
+            background: Some(iced::Background::Color(
+                if is_selected {
+                    palette.primary.weak.color
+                } else {
+                    palette.background.weak.color
+                }
+            )),
+            text_color: if is_selected {
+                palette.primary.weak.text
+            } else {
+                palette.background.weak.text
+            },
+            border: iced::Border {
+                radius: 8.0.into(),
+                ..Default::default()
+            },
+            shadow: Shadow {
+                color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+                offset: iced::Vector::new(0.0, 2.0),
+                blur_radius: 4.0,
+            },
+        };
+        
+        match status {
+            button::Status::Hovered => button::Style {
+                background: Some(iced::Background::Color(palette.background.strong.color)),
+                shadow: Shadow {
+                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.15),
+                    offset: iced::Vector::new(0.0, 4.0),
+                    blur_radius: 8.0,
+                },
+                ..base
+            },
+            button::Status::Pressed => button::Style {
+                background: Some(iced::Background::Color(palette.primary.weak.color)),
+                ..base
+            },
+            _ => base,
+        }
+    })
+    .into()
+}
+
+fn feed_action_button(label: &str, message: SidebarMessage) -> Element<'_, SidebarMessage> {
+    button(text(label).size(13))
+        .on_press(message)
+        .padding([8, 8])
+        .style(|theme: &Theme, status| {
+            let palette = theme.extended_palette();
+            let base = button::Style {
+                background: None,
+                text_color: palette.background.strong.text,
+                border: iced::Border {
+                    radius: 6.0.into(),
+                    ..Default::default()
+                },
+                shadow: Shadow::default(),
+            };
+
+            match status {
+                button::Status::Hovered => button::Style {
+                    background: Some(iced::Background::Color(palette.background.strong.color)),
+                    text_color: palette.background.base.text,
+                    ..base
+                },
+                button::Status::Pressed => button::Style {
+                    background: Some(iced::Background::Color(palette.primary.weak.color)),
+                    ..base
+                },
+                _ => base,
+            }
+        })
+        .into()
+}
+
+/// Full-width button at the bottom of the sidebar
+fn footer_button<'a>(label: impl text::IntoFragment<'a>, message: SidebarMessage) -> Element<'a, SidebarMessage> {
+    button(text(label).size(15))
+        .on_press(message)
+        .padding([12, 24])
+        .width(Length::Fill)
+        .style(|theme: &Theme, status| {
+            let palette = theme.extended_palette();
+            let base = button::Style {
+                background: Some(iced::Background::Color(palette.background.weak.color)),
+                text_color: palette.background.base.text,
+                border: iced::Border {
+                    radius: 8.0.into(),
+                    ..Default::default()
+                },
+                shadow: Shadow {
+                    color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.1),
+                    offset: iced::Vector::new(0.0, 1.0),
+                    blur_radius: 2.0,
+                },
+            };
+            
+            match status {
+                button::Status::Hovered => button::Style {
+                    background: Some(iced::Background::Color(palette.background.strong.color)),
+                    shadow: Shadow {
+                        color: iced::Color::from_rgba(0.0, 0.0, 0.0, 0.15),
+                        offset: iced::Vector::new(0.0, 3.0),
+                        blur_radius: 6.0,
+                    },
+                    ..base
+                },
+                button::Status::Pressed => button::Style {
+                    background: Some(iced::Background::Color(palette.secondary.weak.color)),
+                    ..base
+                },
+                _ => base,
+            }
+        })
+        .into()
+}
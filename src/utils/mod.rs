@@ -1,3 +1,5 @@
 pub mod sanitizer;
+pub mod time;
 
-pub use sanitizer::sanitize_html;
+pub use sanitizer::{excerpt, html_to_text, sanitize_html};
+pub use time::format_relative;
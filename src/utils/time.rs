@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+
+/// Format a date relative to `now`, e.g. "just now", "5m ago", "3h ago",
+/// "2d ago". Dates more than a week old are shown as a plain date.
+pub fn format_relative(date: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(date);
+
+    if elapsed.num_seconds() < 60 {
+        // Also covers dates slightly in the future due to clock skew
+        "just now".to_string()
+    } else if elapsed.num_minutes() < 60 {
+        format!("{}m ago", elapsed.num_minutes())
+    } else if elapsed.num_hours() < 24 {
+        format!("{}h ago", elapsed.num_hours())
+    } else if elapsed.num_days() < 7 {
+        format!("{}d ago", elapsed.num_days())
+    } else {
+        date.format("%b %-d, %Y").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_format_relative() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 10, 12, 0, 0).unwrap();
+        assert_eq!(format_relative(now - Duration::seconds(20), now), "just now");
+        assert_eq!(format_relative(now + Duration::minutes(2), now), "just now");
+        assert_eq!(format_relative(now - Duration::minutes(5), now), "5m ago");
+        assert_eq!(format_relative(now - Duration::hours(3), now), "3h ago");
+        assert_eq!(format_relative(now - Duration::days(2), now), "2d ago");
+        assert_eq!(format_relative(now - Duration::days(30), now), "Feb 9, 2024");
+    }
+}